@@ -0,0 +1,1357 @@
+// program/src/accounts.rs
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+
+use crate::error::SolanaIoError;
+use crate::pda::{
+    create_fee_vault_address, create_global_game_address, create_multisig_address,
+    create_player_state_address, create_reconciliation_address, create_room_address,
+    find_fee_vault_address, find_global_game_address, find_multisig_address,
+    find_player_state_address, find_reconciliation_address, find_room_address,
+};
+use crate::solana_io::{
+    AuthorityRole, EatEvent, GlobalGame, Multisig, PlayerState, Reconciliation, Room,
+};
+
+// Konteksty kont dla każdej instrukcji. `parse` sprawdza signer, writable,
+// unikalność, właściciela i PDA - procesory dostają już zweryfikowane konta
+// i załadowany stan.
+//
+// Wyjątek: podpis authority (serwer/admin) sprawdza procesor przez
+// `check_authority_signature`, bo authority może być kontem multisig - wtedy
+// podpisują członkowie przekazani jako `multisig_signers` na końcu listy kont.
+
+pub fn check_signer(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+pub fn check_writable(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_writable {
+        msg!("Account {} must be writable", account.key);
+        return Err(SolanaIoError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+pub fn check_owner(account: &AccountInfo, owner: &Pubkey) -> Result<(), ProgramError> {
+    if account.owner != owner {
+        msg!("Account {} has wrong owner {}", account.key, account.owner);
+        return Err(SolanaIoError::InvalidAccountOwner.into());
+    }
+    Ok(())
+}
+
+/// To samo konto nie może wystąpić w instrukcji dwa razy
+pub fn check_distinct(accounts: &[&AccountInfo]) -> Result<(), ProgramError> {
+    for (i, a) in accounts.iter().enumerate() {
+        if accounts[i + 1..].iter().any(|b| a.key == b.key) {
+            msg!("Account {} passed more than once", a.key);
+            return Err(SolanaIoError::DuplicateAccount.into());
+        }
+    }
+    Ok(())
+}
+
+pub fn check_system_program(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !system_program::check_id(account.key) {
+        return Err(SolanaIoError::InvalidSystemProgram.into());
+    }
+    Ok(())
+}
+
+pub fn check_rent_sysvar(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !sysvar::rent::check_id(account.key) {
+        return Err(SolanaIoError::InvalidSysvar.into());
+    }
+    Ok(())
+}
+
+/// Ładuje globalną grę: właściciel = program, adres = PDA z zapisanym bumpem
+pub fn load_game(program_id: &Pubkey, account: &AccountInfo) -> Result<GlobalGame, ProgramError> {
+    check_owner(account, program_id)?;
+    let game = GlobalGame::from_account_data(&account.data.borrow())?;
+    if create_global_game_address(program_id, game.bump)? != *account.key {
+        return Err(SolanaIoError::InvalidGameAccount.into());
+    }
+    Ok(game)
+}
+
+/// Ładuje pokój: właściciel = program, adres = PDA z zapisanego id i bumpa
+pub fn load_room(program_id: &Pubkey, account: &AccountInfo) -> Result<Room, ProgramError> {
+    check_owner(account, program_id)?;
+    if account.data_len() != Room::SIZE {
+        return Err(SolanaIoError::InvalidRoomAccount.into());
+    }
+    let room = Room::try_from_slice(&account.data.borrow())
+        .map_err(|_| SolanaIoError::InvalidRoomAccount)?;
+    if !room.is_initialized
+        || create_room_address(program_id, room.room_id, room.bump)? != *account.key
+    {
+        return Err(SolanaIoError::InvalidRoomAccount.into());
+    }
+    Ok(room)
+}
+
+/// Ładuje zapis uzgodnienia: właściciel = program, adres = PDA z zapisanego id pokoju i bumpa
+pub fn load_reconciliation(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+) -> Result<Reconciliation, ProgramError> {
+    check_owner(account, program_id)?;
+    let reconciliation = Reconciliation::try_from_slice(&account.data.borrow())
+        .map_err(|_| SolanaIoError::InvalidReconciliationAccount)?;
+    if !reconciliation.is_initialized
+        || create_reconciliation_address(program_id, reconciliation.room_id, reconciliation.bump)?
+            != *account.key
+    {
+        return Err(SolanaIoError::InvalidReconciliationAccount.into());
+    }
+    Ok(reconciliation)
+}
+
+/// Gracz musi grać w przekazanym pokoju - inaczej serwer jednego pokoju
+/// mógłby ruszać pulę innego
+pub fn check_player_room(player_state: &PlayerState, room: &Room) -> Result<(), ProgramError> {
+    if player_state.room_id != room.room_id {
+        msg!(
+            "Player {} is in room {}, not {}",
+            player_state.pubkey,
+            player_state.room_id,
+            room.room_id
+        );
+        return Err(SolanaIoError::PlayerRoomMismatch.into());
+    }
+    Ok(())
+}
+
+/// Ładuje stan dowolnego gracza - PDA sprawdzane z adresem gracza zapisanym w stanie
+pub fn load_any_player_state(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+) -> Result<PlayerState, ProgramError> {
+    check_owner(account, program_id)?;
+    let player = PlayerState::try_from_slice(&account.data.borrow())
+        .map_err(|_| SolanaIoError::InvalidPlayerStateAccount)?
+        .pubkey;
+    load_player_state(program_id, account, &player)
+}
+
+/// Sprawdza skarbiec prowizji: właściciel = program, adres = PDA z bumpem zapisanym w grze
+pub fn check_fee_vault(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    game: &GlobalGame,
+) -> Result<(), ProgramError> {
+    check_owner(account, program_id)?;
+    if create_fee_vault_address(program_id, game.fee_vault_bump)? != *account.key {
+        return Err(SolanaIoError::InvalidFeeVaultAccount.into());
+    }
+    Ok(())
+}
+
+/// Ładuje multisig: właściciel = program, adres = PDA multisig z zapisanej roli i bumpa
+pub fn load_multisig(program_id: &Pubkey, account: &AccountInfo) -> Result<Multisig, ProgramError> {
+    check_owner(account, program_id)?;
+    if account.data_len() != Multisig::SIZE {
+        return Err(SolanaIoError::InvalidMultisigAccount.into());
+    }
+    let multisig = Multisig::try_from_slice(&account.data.borrow())
+        .map_err(|_| SolanaIoError::InvalidMultisigAccount)?;
+    if !multisig.is_initialized
+        || create_multisig_address(program_id, multisig.role, multisig.bump)? != *account.key
+    {
+        return Err(SolanaIoError::InvalidMultisigAccount.into());
+    }
+    Ok(multisig)
+}
+
+/// Podpis authority: zwykły klucz musi podpisać transakcję, a konto multisig
+/// wymaga podpisów co najmniej `threshold` różnych członków wśród `signers`
+pub fn check_authority_signature(
+    program_id: &Pubkey,
+    authority_account: &AccountInfo,
+    signers: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    if authority_account.is_signer {
+        return Ok(());
+    }
+    if authority_account.owner != program_id {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let multisig = load_multisig(program_id, authority_account)?;
+    let signed = multisig
+        .members()
+        .iter()
+        .filter(|member| signers.iter().any(|s| s.is_signer && s.key == *member))
+        .count();
+    if signed < multisig.threshold as usize {
+        msg!(
+            "Multisig {}: {} of {} required signatures",
+            authority_account.key,
+            signed,
+            multisig.threshold
+        );
+        return Err(SolanaIoError::MultisigThresholdNotMet.into());
+    }
+    Ok(())
+}
+
+/// Ładuje stan gracza: właściciel = program, gracz = `player`, adres = PDA z zapisanym bumpem
+pub fn load_player_state(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    player: &Pubkey,
+) -> Result<PlayerState, ProgramError> {
+    check_owner(account, program_id)?;
+    let player_state = PlayerState::try_from_slice(&account.data.borrow())?;
+    if player_state.pubkey != *player
+        || create_player_state_address(program_id, player, player_state.bump)? != *account.key
+    {
+        return Err(SolanaIoError::InvalidPlayerStateAccount.into());
+    }
+    Ok(player_state)
+}
+
+pub struct InitializeGameAccounts<'a, 'info> {
+    pub initializer: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub fee_vault_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent_account: &'a AccountInfo<'info>,
+    pub game_bump: u8,
+    pub fee_vault_bump: u8,
+}
+
+impl<'a, 'info> InitializeGameAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let initializer = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let fee_vault_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let rent_account = next_account_info(accounts_iter)?;
+
+        check_signer(initializer)?;
+        check_writable(initializer)?;
+        check_writable(game_account)?;
+        check_writable(fee_vault_account)?;
+        check_distinct(&[initializer, game_account, fee_vault_account])?;
+        check_system_program(system_program)?;
+        check_rent_sysvar(rent_account)?;
+
+        // Weryfikacja PDA dla globalnej gry
+        let (expected_game_pubkey, game_bump) = find_global_game_address(program_id);
+        if expected_game_pubkey != *game_account.key {
+            return Err(SolanaIoError::InvalidGameAccount.into());
+        }
+
+        // Sprawdź czy gra już jest zainicjalizowana
+        if !game_account.data_is_empty() {
+            return Err(SolanaIoError::GameAlreadyInitialized.into());
+        }
+
+        let (expected_fee_vault_pubkey, fee_vault_bump) = find_fee_vault_address(program_id);
+        if expected_fee_vault_pubkey != *fee_vault_account.key {
+            return Err(SolanaIoError::InvalidFeeVaultAccount.into());
+        }
+
+        Ok(Self {
+            initializer,
+            game_account,
+            fee_vault_account,
+            system_program,
+            rent_account,
+            game_bump,
+            fee_vault_bump,
+        })
+    }
+}
+
+pub struct JoinGameAccounts<'a, 'info> {
+    pub player_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent_account: &'a AccountInfo<'info>,
+    pub game: GlobalGame,
+    pub room: Room,
+    /// None - gracz dołącza pierwszy raz i konto trzeba utworzyć
+    pub player_state: Option<PlayerState>,
+    pub player_state_bump: u8,
+}
+
+impl<'a, 'info> JoinGameAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        room_id: u32,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let player_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let rent_account = next_account_info(accounts_iter)?;
+
+        check_signer(player_account)?;
+        check_writable(player_account)?;
+        check_writable(player_state_account)?;
+        check_writable(room_account)?;
+        check_distinct(&[player_account, player_state_account, game_account, room_account])?;
+        check_system_program(system_program)?;
+        check_rent_sysvar(rent_account)?;
+
+        // Konfiguracja tylko do odczytu - dołączenia blokują wyłącznie swój pokój
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        if room.room_id != room_id {
+            return Err(SolanaIoError::InvalidRoomAccount.into());
+        }
+
+        let (player_state, player_state_bump) = if player_state_account.data_is_empty() {
+            // Nowy gracz - bump liczymy raz i zapisujemy w stanie
+            let (expected_player_state_pubkey, bump) =
+                find_player_state_address(program_id, player_account.key);
+            if expected_player_state_pubkey != *player_state_account.key {
+                return Err(SolanaIoError::InvalidPlayerStateAccount.into());
+            }
+            (None, bump)
+        } else {
+            let player_state = load_player_state(program_id, player_state_account, player_account.key)?;
+            let bump = player_state.bump;
+            (Some(player_state), bump)
+        };
+
+        Ok(Self {
+            player_account,
+            player_state_account,
+            game_account,
+            room_account,
+            system_program,
+            rent_account,
+            game,
+            room,
+            player_state,
+            player_state_bump,
+        })
+    }
+}
+
+pub struct TopUpAccounts<'a, 'info> {
+    pub player_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub game: GlobalGame,
+    pub room: Room,
+    pub player_state: PlayerState,
+}
+
+impl<'a, 'info> TopUpAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let player_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+
+        check_signer(player_account)?;
+        check_writable(player_account)?;
+        check_writable(player_state_account)?;
+        check_writable(room_account)?;
+        check_distinct(&[player_account, player_state_account, game_account, room_account])?;
+        check_system_program(system_program)?;
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        let player_state = load_player_state(program_id, player_state_account, player_account.key)?;
+        check_player_room(&player_state, &room)?;
+
+        Ok(Self {
+            player_account,
+            player_state_account,
+            room_account,
+            system_program,
+            game,
+            room,
+            player_state,
+        })
+    }
+}
+
+pub struct UpdatePlayerValueAccounts<'a, 'info> {
+    pub authority_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub eaten_player_state_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub room: Room,
+    pub player_state: PlayerState,
+    pub eaten_player_state: PlayerState,
+}
+
+impl<'a, 'info> UpdatePlayerValueAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        player: &Pubkey,
+        eaten_player: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+        let eaten_player_state_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        // Gracz nie może zjeść samego siebie
+        if player == eaten_player {
+            return Err(SolanaIoError::SelfEatNotAllowed.into());
+        }
+
+        check_writable(player_state_account)?;
+        check_writable(eaten_player_state_account)?;
+        check_writable(room_account)?;
+        check_distinct(&[
+            authority_account,
+            player_state_account,
+            eaten_player_state_account,
+            game_account,
+            room_account,
+        ])?;
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        // Oba konta muszą być PDA graczy z instrukcji, grających w tym pokoju
+        let player_state = load_player_state(program_id, player_state_account, player)?;
+        let eaten_player_state =
+            load_player_state(program_id, eaten_player_state_account, eaten_player)?;
+        check_player_room(&player_state, &room)?;
+        check_player_room(&eaten_player_state, &room)?;
+
+        Ok(Self {
+            authority_account,
+            player_state_account,
+            eaten_player_state_account,
+            game_account,
+            room_account,
+            multisig_signers,
+            game,
+            room,
+            player_state,
+            eaten_player_state,
+        })
+    }
+}
+
+pub struct CashOutAccounts<'a, 'info> {
+    pub player_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub instructions_sysvar: &'a AccountInfo<'info>,
+    pub game: GlobalGame,
+    pub room: Room,
+    pub player_state: PlayerState,
+    /// Multisig serwera - tylko gdy server authority jest kontem multisig
+    pub server_multisig: Option<Multisig>,
+}
+
+impl<'a, 'info> CashOutAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let player_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let instructions_sysvar = next_account_info(accounts_iter)?;
+        let server_multisig_account = accounts_iter.next();
+
+        check_signer(player_account)?;
+        check_writable(player_account)?;
+        check_writable(player_state_account)?;
+        // Prowizja zostaje na koncie pokoju - konfiguracja jest tylko do odczytu
+        check_writable(room_account)?;
+        check_distinct(&[
+            player_account,
+            player_state_account,
+            game_account,
+            room_account,
+        ])?;
+        if !sysvar::instructions::check_id(instructions_sysvar.key) {
+            return Err(SolanaIoError::InvalidSysvar.into());
+        }
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        let player_state = load_player_state(program_id, player_state_account, player_account.key)?;
+        check_player_room(&player_state, &room)?;
+
+        let server_multisig = match server_multisig_account {
+            Some(account) => {
+                if *account.key != game.server_authority {
+                    return Err(SolanaIoError::InvalidMultisigAccount.into());
+                }
+                Some(load_multisig(program_id, account)?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            player_account,
+            player_state_account,
+            game_account,
+            room_account,
+            instructions_sysvar,
+            game,
+            room,
+            player_state,
+            server_multisig,
+        })
+    }
+}
+
+pub struct UpdateGameParamsAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+}
+
+impl<'a, 'info> UpdateGameParamsAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(game_account)?;
+        check_distinct(&[admin_account, game_account])?;
+
+        let game = load_game(program_id, game_account)?;
+
+        Ok(Self {
+            admin_account,
+            game_account,
+            multisig_signers,
+            game,
+        })
+    }
+}
+
+pub struct ForceCleanupAccounts<'a, 'info> {
+    pub authority_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub player_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub room: Room,
+    pub player_state: PlayerState,
+}
+
+impl<'a, 'info> ForceCleanupAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        player: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let player_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(player_state_account)?;
+        check_writable(room_account)?;
+        check_distinct(&[
+            authority_account,
+            player_state_account,
+            game_account,
+            room_account,
+            player_account,
+        ])?;
+
+        // Sprawdź czy player account jest poprawny
+        if player_account.key != player {
+            return Err(SolanaIoError::PlayerAccountMismatch.into());
+        }
+
+        let game = load_game(program_id, game_account)?;
+
+        let room = load_room(program_id, room_account)?;
+        let player_state = load_player_state(program_id, player_state_account, player)?;
+        check_player_room(&player_state, &room)?;
+
+        Ok(Self {
+            authority_account,
+            player_state_account,
+            game_account,
+            room_account,
+            player_account,
+            multisig_signers,
+            game,
+            room,
+            player_state,
+        })
+    }
+}
+
+pub struct ForceRefundAccounts<'a, 'info> {
+    pub authority_account: &'a AccountInfo<'info>,
+    pub player_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub room: Room,
+    pub player_state: PlayerState,
+}
+
+impl<'a, 'info> ForceRefundAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        player: &Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let player_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(player_account)?;
+        check_writable(player_state_account)?;
+        // Prowizja zostaje na koncie pokoju - konfiguracja jest tylko do odczytu
+        check_writable(room_account)?;
+        check_distinct(&[
+            authority_account,
+            player_account,
+            player_state_account,
+            game_account,
+            room_account,
+        ])?;
+
+        if player_account.key != player {
+            return Err(SolanaIoError::PlayerAccountMismatch.into());
+        }
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        let player_state = load_player_state(program_id, player_state_account, player)?;
+        check_player_room(&player_state, &room)?;
+
+        Ok(Self {
+            authority_account,
+            player_account,
+            player_state_account,
+            game_account,
+            room_account,
+            multisig_signers,
+            game,
+            room,
+            player_state,
+        })
+    }
+}
+
+pub struct WithdrawFeesAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub fee_vault_account: &'a AccountInfo<'info>,
+    pub recipient_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+}
+
+impl<'a, 'info> WithdrawFeesAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let fee_vault_account = next_account_info(accounts_iter)?;
+        let recipient_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(game_account)?;
+        check_writable(fee_vault_account)?;
+        check_writable(recipient_account)?;
+        check_distinct(&[
+            admin_account,
+            game_account,
+            fee_vault_account,
+            recipient_account,
+        ])?;
+
+        let game = load_game(program_id, game_account)?;
+        check_fee_vault(program_id, fee_vault_account, &game)?;
+
+        Ok(Self {
+            admin_account,
+            game_account,
+            fee_vault_account,
+            recipient_account,
+            multisig_signers,
+            game,
+        })
+    }
+}
+
+/// Konta ProposeAuthority, AcceptAuthority i CancelProposal: [authority, game PDA, ...multisig signers].
+/// Który klucz ma podpisać, zależy od instrukcji - sprawdza to procesor.
+pub struct AuthorityTransferAccounts<'a, 'info> {
+    pub authority_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+}
+
+impl<'a, 'info> AuthorityTransferAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(game_account)?;
+        check_distinct(&[authority_account, game_account])?;
+
+        let game = load_game(program_id, game_account)?;
+
+        Ok(Self {
+            authority_account,
+            game_account,
+            multisig_signers,
+            game,
+        })
+    }
+}
+
+pub struct CreateMultisigAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub multisig_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub multisig_bump: u8,
+}
+
+impl<'a, 'info> CreateMultisigAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        role: AuthorityRole,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let payer = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let multisig_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let rent_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_signer(payer)?;
+        check_writable(payer)?;
+        check_writable(multisig_account)?;
+        check_distinct(&[admin_account, game_account, multisig_account])?;
+        check_distinct(&[payer, game_account, multisig_account])?;
+        check_system_program(system_program)?;
+        check_rent_sysvar(rent_account)?;
+
+        let game = load_game(program_id, game_account)?;
+
+        let (expected_multisig_pubkey, multisig_bump) = find_multisig_address(program_id, role);
+        if expected_multisig_pubkey != *multisig_account.key {
+            return Err(SolanaIoError::InvalidMultisigAccount.into());
+        }
+        if !multisig_account.data_is_empty() {
+            return Err(SolanaIoError::MultisigAlreadyExists.into());
+        }
+
+        Ok(Self {
+            admin_account,
+            payer,
+            game_account,
+            multisig_account,
+            system_program,
+            rent_account,
+            multisig_signers,
+            game,
+            multisig_bump,
+        })
+    }
+}
+
+pub struct SetMultisigSignersAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub multisig_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub multisig: Multisig,
+}
+
+impl<'a, 'info> SetMultisigSignersAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        role: AuthorityRole,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let multisig_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(multisig_account)?;
+        // Admin może być tym samym multisig, którego członków zmienia
+        check_distinct(&[admin_account, game_account])?;
+        check_distinct(&[game_account, multisig_account])?;
+
+        let game = load_game(program_id, game_account)?;
+        let multisig = load_multisig(program_id, multisig_account)?;
+        if multisig.role != role {
+            return Err(SolanaIoError::InvalidMultisigAccount.into());
+        }
+
+        Ok(Self {
+            admin_account,
+            game_account,
+            multisig_account,
+            multisig_signers,
+            game,
+            multisig,
+        })
+    }
+}
+
+pub struct SetPausedAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+}
+
+impl<'a, 'info> SetPausedAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(game_account)?;
+        check_distinct(&[admin_account, game_account])?;
+
+        let game = load_game(program_id, game_account)?;
+
+        Ok(Self {
+            admin_account,
+            game_account,
+            multisig_signers,
+            game,
+        })
+    }
+}
+
+pub struct HeartbeatAccounts<'a, 'info> {
+    pub authority_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+}
+
+impl<'a, 'info> HeartbeatAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(game_account)?;
+        check_distinct(&[authority_account, game_account])?;
+
+        let game = load_game(program_id, game_account)?;
+
+        Ok(Self {
+            authority_account,
+            game_account,
+            multisig_signers,
+            game,
+        })
+    }
+}
+
+pub struct EmergencyWithdrawAccounts<'a, 'info> {
+    pub player_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub game: GlobalGame,
+    pub room: Room,
+    pub player_state: PlayerState,
+}
+
+impl<'a, 'info> EmergencyWithdrawAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let player_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+
+        check_signer(player_account)?;
+        check_writable(player_account)?;
+        check_writable(player_state_account)?;
+        check_writable(room_account)?;
+        check_distinct(&[player_account, player_state_account, game_account, room_account])?;
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        let player_state = load_player_state(program_id, player_state_account, player_account.key)?;
+        check_player_room(&player_state, &room)?;
+
+        Ok(Self {
+            player_account,
+            player_state_account,
+            game_account,
+            room_account,
+            game,
+            room,
+            player_state,
+        })
+    }
+}
+
+pub struct ClosePlayerStateAccounts<'a, 'info> {
+    pub player_account: &'a AccountInfo<'info>,
+    pub player_state_account: &'a AccountInfo<'info>,
+    pub player_state: PlayerState,
+}
+
+impl<'a, 'info> ClosePlayerStateAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let player_account = next_account_info(accounts_iter)?;
+        let player_state_account = next_account_info(accounts_iter)?;
+
+        check_signer(player_account)?;
+        check_writable(player_account)?;
+        check_writable(player_state_account)?;
+        check_distinct(&[player_account, player_state_account])?;
+
+        let player_state = load_player_state(program_id, player_state_account, player_account.key)?;
+
+        Ok(Self {
+            player_account,
+            player_state_account,
+            player_state,
+        })
+    }
+}
+
+pub struct CheckSolvencyAccounts<'a, 'info> {
+    pub game_account: &'a AccountInfo<'info>,
+    pub fee_vault_account: &'a AccountInfo<'info>,
+    pub room_accounts: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    /// Pokoje w kolejności `room_accounts`
+    pub rooms: Vec<Room>,
+}
+
+impl<'a, 'info> CheckSolvencyAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let game_account = next_account_info(accounts_iter)?;
+        let fee_vault_account = next_account_info(accounts_iter)?;
+        // Pozostałe konta to pokoje do sprawdzenia
+        let room_accounts = accounts_iter.as_slice();
+
+        let game = load_game(program_id, game_account)?;
+        check_fee_vault(program_id, fee_vault_account, &game)?;
+        let rooms = room_accounts
+            .iter()
+            .map(|account| load_room(program_id, account))
+            .collect::<Result<Vec<_>, _>>()?;
+        check_distinct(&room_accounts.iter().collect::<Vec<_>>())?;
+
+        Ok(Self {
+            game_account,
+            fee_vault_account,
+            room_accounts,
+            game,
+            rooms,
+        })
+    }
+}
+
+pub struct ReconcileAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub reconciliation_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub room: Room,
+    /// Stany graczy tej strony, w kolejności kont
+    pub player_states: Vec<PlayerState>,
+    /// None - pierwsze uzgodnienie pokoju, konto trzeba utworzyć
+    pub reconciliation: Option<Reconciliation>,
+    pub reconciliation_bump: u8,
+}
+
+impl<'a, 'info> ReconcileAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        room_id: u32,
+        player_count: u8,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let payer = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let reconciliation_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let rent_account = next_account_info(accounts_iter)?;
+        let player_state_accounts = accounts_iter
+            .as_slice()
+            .get(..player_count as usize)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let multisig_signers = &accounts_iter.as_slice()[player_count as usize..];
+
+        check_signer(payer)?;
+        check_writable(payer)?;
+        check_writable(room_account)?;
+        check_writable(reconciliation_account)?;
+        check_distinct(&[
+            admin_account,
+            game_account,
+            room_account,
+            reconciliation_account,
+        ])?;
+        check_distinct(&[payer, game_account, room_account, reconciliation_account])?;
+        check_system_program(system_program)?;
+        check_rent_sysvar(rent_account)?;
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        if room.room_id != room_id {
+            return Err(SolanaIoError::InvalidRoomAccount.into());
+        }
+
+        let (reconciliation, reconciliation_bump) = if reconciliation_account.data_is_empty() {
+            let (expected_pubkey, bump) = find_reconciliation_address(program_id, room_id);
+            if expected_pubkey != *reconciliation_account.key {
+                return Err(SolanaIoError::InvalidReconciliationAccount.into());
+            }
+            (None, bump)
+        } else {
+            let reconciliation = load_reconciliation(program_id, reconciliation_account)?;
+            if reconciliation.room_id != room_id {
+                return Err(SolanaIoError::InvalidReconciliationAccount.into());
+            }
+            let bump = reconciliation.bump;
+            (Some(reconciliation), bump)
+        };
+
+        // Różne adresy graczy wymusza rosnąca kolejność sprawdzana w procesorze
+        let player_states = player_state_accounts
+            .iter()
+            .map(|account| {
+                let player_state = load_any_player_state(program_id, account)?;
+                check_player_room(&player_state, &room)?;
+                Ok(player_state)
+            })
+            .collect::<Result<Vec<_>, ProgramError>>()?;
+
+        Ok(Self {
+            admin_account,
+            payer,
+            game_account,
+            room_account,
+            reconciliation_account,
+            system_program,
+            rent_account,
+            multisig_signers,
+            game,
+            room,
+            player_states,
+            reconciliation,
+            reconciliation_bump,
+        })
+    }
+}
+
+pub struct CreateRoomAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub room_bump: u8,
+}
+
+impl<'a, 'info> CreateRoomAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        room_id: u32,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let payer = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let system_program = next_account_info(accounts_iter)?;
+        let rent_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_signer(payer)?;
+        check_writable(payer)?;
+        check_writable(room_account)?;
+        check_distinct(&[admin_account, game_account, room_account])?;
+        check_distinct(&[payer, game_account, room_account])?;
+        check_system_program(system_program)?;
+        check_rent_sysvar(rent_account)?;
+
+        let game = load_game(program_id, game_account)?;
+
+        let (expected_room_pubkey, room_bump) = find_room_address(program_id, room_id);
+        if expected_room_pubkey != *room_account.key {
+            return Err(SolanaIoError::InvalidRoomAccount.into());
+        }
+        if !room_account.data_is_empty() {
+            return Err(SolanaIoError::RoomAlreadyExists.into());
+        }
+
+        Ok(Self {
+            admin_account,
+            payer,
+            game_account,
+            room_account,
+            system_program,
+            rent_account,
+            multisig_signers,
+            game,
+            room_bump,
+        })
+    }
+}
+
+pub struct UpdateRoomParamsAccounts<'a, 'info> {
+    pub admin_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub room: Room,
+}
+
+impl<'a, 'info> UpdateRoomParamsAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        room_id: u32,
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let admin_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(room_account)?;
+        check_distinct(&[admin_account, game_account, room_account])?;
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        if room.room_id != room_id {
+            return Err(SolanaIoError::InvalidRoomAccount.into());
+        }
+
+        Ok(Self {
+            admin_account,
+            game_account,
+            room_account,
+            multisig_signers,
+            game,
+            room,
+        })
+    }
+}
+
+/// Konta CollectRoomFees: [room PDA, game PDA, fee vault PDA]. Bez podpisu -
+/// przeniesienie prowizji do skarbca nie zmienia, do kogo należą środki.
+pub struct CollectRoomFeesAccounts<'a, 'info> {
+    pub room_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub fee_vault_account: &'a AccountInfo<'info>,
+    pub game: GlobalGame,
+    pub room: Room,
+}
+
+impl<'a, 'info> CollectRoomFeesAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        let room_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let fee_vault_account = next_account_info(accounts_iter)?;
+
+        check_writable(room_account)?;
+        check_writable(game_account)?;
+        check_writable(fee_vault_account)?;
+        check_distinct(&[room_account, game_account, fee_vault_account])?;
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        check_fee_vault(program_id, fee_vault_account, &game)?;
+
+        Ok(Self {
+            room_account,
+            game_account,
+            fee_vault_account,
+            game,
+            room,
+        })
+    }
+}
+
+pub struct BatchUpdatePlayerValuesAccounts<'a, 'info> {
+    pub authority_account: &'a AccountInfo<'info>,
+    pub game_account: &'a AccountInfo<'info>,
+    pub room_account: &'a AccountInfo<'info>,
+    /// Konta stanu w kolejności `EatEvent::players`
+    pub player_state_accounts: Vec<&'a AccountInfo<'info>>,
+    pub multisig_signers: &'a [AccountInfo<'info>],
+    pub game: GlobalGame,
+    pub room: Room,
+    pub player_states: Vec<PlayerState>,
+}
+
+impl<'a, 'info> BatchUpdatePlayerValuesAccounts<'a, 'info> {
+    pub fn parse(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+        events: &[EatEvent],
+    ) -> Result<Self, ProgramError> {
+        if events.is_empty() {
+            return Err(SolanaIoError::EmptyEatBatch.into());
+        }
+        if events.iter().any(|event| event.player == event.eaten_player) {
+            return Err(SolanaIoError::SelfEatNotAllowed.into());
+        }
+
+        let accounts_iter = &mut accounts.iter();
+        let authority_account = next_account_info(accounts_iter)?;
+        let game_account = next_account_info(accounts_iter)?;
+        let room_account = next_account_info(accounts_iter)?;
+        // Liczba kont stanu wynika z samych zdarzeń - reszta to członkowie multisig
+        let players = EatEvent::players(events);
+        let player_state_accounts = players
+            .iter()
+            .map(|_| next_account_info(accounts_iter))
+            .collect::<Result<Vec<_>, _>>()?;
+        let multisig_signers = accounts_iter.as_slice();
+
+        check_writable(room_account)?;
+        let mut all = vec![authority_account, game_account, room_account];
+        for account in &player_state_accounts {
+            check_writable(account)?;
+            all.push(account);
+        }
+        check_distinct(&all)?;
+
+        let game = load_game(program_id, game_account)?;
+        let room = load_room(program_id, room_account)?;
+        // Każde konto musi być PDA swojego gracza, grającego w tym pokoju
+        let player_states = players
+            .iter()
+            .zip(player_state_accounts.iter())
+            .map(|(player, account)| {
+                let player_state = load_player_state(program_id, account, player)?;
+                check_player_room(&player_state, &room)?;
+                Ok(player_state)
+            })
+            .collect::<Result<Vec<_>, ProgramError>>()?;
+
+        Ok(Self {
+            authority_account,
+            game_account,
+            room_account,
+            player_state_accounts,
+            multisig_signers,
+            game,
+            room,
+            player_states,
+        })
+    }
+}
@@ -0,0 +1,135 @@
+// program/src/bin/indexer.rs
+use std::{env, fs, process, str::FromStr};
+
+use solana_io::indexer::{read_log_file, IndexedTransaction, Projection};
+use solana_program::pubkey::Pubkey;
+
+// Buduje projekcję z nagranych logów (pliki w formacie `read_log_file`) albo
+// z RPC walidatora (feature `rpc`) i wypisuje raport: gracze, zjedzenia, prowizje.
+
+const USAGE: &str = "usage:
+  indexer <program-id> <log-file>...
+  indexer <program-id> --rpc <url> [--limit <n>] [--save <log-file>]";
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn load_files(paths: &[String]) -> Vec<IndexedTransaction> {
+    let mut transactions = Vec::new();
+    for path in paths {
+        let input = fs::read_to_string(path)
+            .unwrap_or_else(|e| fail(&format!("cannot read {}: {}", path, e)));
+        let file = read_log_file(&input).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+        transactions.extend(file);
+    }
+    transactions
+}
+
+#[cfg(feature = "rpc")]
+fn load_rpc(program_id: &Pubkey, args: &[String]) -> Vec<IndexedTransaction> {
+    use solana_client::rpc_client::RpcClient;
+    use solana_io::indexer::{rpc::fetch_transactions, write_log_file};
+
+    let mut url = None;
+    let mut limit = 10_000;
+    let mut save = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| fail(USAGE)).clone();
+        match arg.as_str() {
+            "--rpc" => url = Some(value()),
+            "--limit" => limit = value().parse().unwrap_or_else(|_| fail(USAGE)),
+            "--save" => save = Some(value()),
+            _ => fail(USAGE),
+        }
+    }
+
+    let client = RpcClient::new(url.unwrap_or_else(|| fail(USAGE)));
+    let transactions = fetch_transactions(&client, program_id, None, limit)
+        .unwrap_or_else(|e| fail(&format!("RPC error: {}", e)));
+    if let Some(path) = save {
+        fs::write(&path, write_log_file(&transactions))
+            .unwrap_or_else(|e| fail(&format!("cannot write {}: {}", path, e)));
+    }
+    transactions
+}
+
+#[cfg(not(feature = "rpc"))]
+fn load_rpc(_program_id: &Pubkey, _args: &[String]) -> Vec<IndexedTransaction> {
+    fail("indexer was built without the `rpc` feature")
+}
+
+/// Data (rok, miesiąc, dzień) z liczby dni od epoki unixowej
+fn civil_date(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn print_report(projection: &Projection) {
+    if let Some(game) = &projection.game {
+        println!("server authority:    {}", game.server_authority);
+        println!("admin authority:     {}", game.admin_authority);
+        println!("platform fee wallet: {}", game.platform_fee_wallet);
+    }
+
+    let mut players: Vec<_> = projection.players.iter().collect();
+    players.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.pnl()));
+    println!("\nplayers: {}", players.len());
+    for (player, stats) in players {
+        println!(
+            "  {} sessions={} staked={} payout={} fees={} kills={} deaths={} refunds={} pnl={}",
+            player,
+            stats.sessions,
+            stats.total_staked,
+            stats.total_payout,
+            stats.fees_paid,
+            stats.kills,
+            stats.deaths,
+            stats.refunds,
+            stats.pnl()
+        );
+    }
+
+    let open = projection
+        .sessions
+        .iter()
+        .filter(|s| s.end.is_none())
+        .count();
+    println!("\nsessions: {} ({} open)", projection.sessions.len(), open);
+    println!("kills: {}", projection.kills.len());
+
+    println!("\nfees: {} lamports", projection.total_fees);
+    for (day, fees) in &projection.fees_by_day {
+        let (year, month, day) = civil_date(*day);
+        println!("  {:04}-{:02}-{:02} {}", year, month, day, fees);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        fail(USAGE);
+    }
+    let program_id = Pubkey::from_str(&args[0]).unwrap_or_else(|_| fail("invalid program id"));
+
+    let transactions = if args[1].starts_with("--") {
+        load_rpc(&program_id, &args[1..])
+    } else {
+        load_files(&args[1..])
+    };
+
+    let mut projection = Projection::new();
+    let events = projection.apply_all(&program_id, &transactions);
+    println!("{} transactions, {} events\n", transactions.len(), events);
+    print_report(&projection);
+}
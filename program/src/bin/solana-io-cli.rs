@@ -0,0 +1,266 @@
+// program/src/bin/solana-io-cli.rs
+use std::{env, process};
+
+use borsh::BorshDeserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_io::{
+    cli::{
+        format_game, format_player, format_player_row, format_sol, parse_args,
+        rpc::{fetch_player_states, fetch_rooms, reconcile_room},
+        Command, Config, PROGRAM_ID_ENV,
+    },
+    instruction,
+    pda::{find_fee_vault_address, find_global_game_address, find_player_state_address},
+    solana_io::{GlobalGame, PlayerState, Room},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+// Narzędzie operatora: wysyła instrukcje z `solana_io::instruction` podpisane
+// kluczem z pliku i czyta konta programu typami z tej samej skrzynki.
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn program_id(config: &Config) -> Pubkey {
+    config.program_id.unwrap_or_else(|| {
+        env::var(PROGRAM_ID_ENV)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_else(|| fail(&format!("missing --program-id (or {})", PROGRAM_ID_ENV)))
+    })
+}
+
+fn signer(config: &Config) -> Keypair {
+    let path = config.keypair_path.clone().unwrap_or_else(|| {
+        let home = env::var("HOME").unwrap_or_else(|_| fail("HOME is not set, use --keypair"));
+        format!("{}/.config/solana/id.json", home)
+    });
+    read_keypair_file(&path).unwrap_or_else(|e| fail(&format!("cannot read {}: {}", path, e)))
+}
+
+fn send(client: &RpcClient, signer: &Keypair, instructions: &[Instruction]) {
+    let blockhash = client
+        .get_latest_blockhash()
+        .unwrap_or_else(|e| fail(&format!("RPC error: {}", e)));
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signer.pubkey()),
+        &[signer],
+        blockhash,
+    );
+    let signature = client
+        .send_and_confirm_transaction(&transaction)
+        .unwrap_or_else(|e| fail(&format!("transaction failed: {}", e)));
+    println!("signature: {}", signature);
+}
+
+fn load_game(client: &RpcClient, program_id: &Pubkey) -> GlobalGame {
+    let address = find_global_game_address(program_id).0;
+    let data = client
+        .get_account_data(&address)
+        .unwrap_or_else(|e| fail(&format!("cannot load game {}: {}", address, e)));
+    GlobalGame::from_account_data(&data).unwrap_or_else(|_| fail("game account is not initialized"))
+}
+
+fn load_player(client: &RpcClient, program_id: &Pubkey, wallet: &Pubkey) -> PlayerState {
+    let address = find_player_state_address(program_id, wallet).0;
+    let data = client
+        .get_account_data(&address)
+        .unwrap_or_else(|e| fail(&format!("no player state for {}: {}", wallet, e)));
+    PlayerState::try_from_slice(&data).unwrap_or_else(|_| fail("invalid player state account"))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (config, command) = parse_args(&args).unwrap_or_else(|e| fail(&e.to_string()));
+    let program_id = program_id(&config);
+    let client = RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+
+    match command {
+        Command::Init {
+            server_authority,
+            admin_authority,
+            platform_fee_wallet,
+            room_id,
+        } => {
+            let signer = signer(&config);
+            let mut instructions = vec![instruction::initialize_game(
+                &program_id,
+                &signer.pubkey(),
+                &server_authority,
+                &admin_authority,
+                &platform_fee_wallet,
+            )];
+            if let Some(room_id) = room_id {
+                // CreateRoom podpisuje admin - w jednej transakcji tylko gdy to ten sam klucz
+                if admin_authority != signer.pubkey() {
+                    fail("--room requires the signer to be the admin authority");
+                }
+                instructions.push(instruction::create_room(
+                    &program_id,
+                    &admin_authority,
+                    &signer.pubkey(),
+                    room_id,
+                ));
+            }
+            send(&client, &signer, &instructions);
+        }
+        Command::SetParams {
+            min_stake,
+            max_stake,
+            platform_fee_percent,
+            platform_fee_wallet,
+            heartbeat_timeout,
+        } => {
+            let signer = signer(&config);
+            send(
+                &client,
+                &signer,
+                &[instruction::update_game_params(
+                    &program_id,
+                    &signer.pubkey(),
+                    min_stake,
+                    max_stake,
+                    platform_fee_percent,
+                    platform_fee_wallet,
+                    heartbeat_timeout,
+                )],
+            );
+            if min_stake.is_some() || max_stake.is_some() {
+                println!(
+                    "stake limits apply to new rooms only, existing rooms are unchanged (use room-params)"
+                );
+            }
+        }
+        Command::RoomParams {
+            room_id,
+            min_stake,
+            max_stake,
+        } => {
+            let signer = signer(&config);
+            send(
+                &client,
+                &signer,
+                &[instruction::update_room_params(
+                    &program_id,
+                    &signer.pubkey(),
+                    room_id,
+                    min_stake,
+                    max_stake,
+                )],
+            );
+        }
+        Command::ShowGame => {
+            let game = load_game(&client, &program_id);
+            let fee_vault = client
+                .get_balance(&find_fee_vault_address(&program_id).0)
+                .unwrap_or_else(|e| fail(&format!("RPC error: {}", e)));
+            println!("program:               {}", program_id);
+            println!("{}", format_game(&game, fee_vault));
+        }
+        Command::ShowPlayer { wallet } => {
+            println!(
+                "{}",
+                format_player(&load_player(&client, &program_id, &wallet))
+            );
+        }
+        Command::ListPlayers {
+            room_id,
+            active_only,
+        } => {
+            let mut players: Vec<PlayerState> = fetch_player_states(&client, &program_id, room_id)
+                .unwrap_or_else(|e| fail(&format!("RPC error: {}", e)))
+                .into_iter()
+                .filter(|player| !active_only || player.is_active)
+                .collect();
+            players.sort_by_key(|player| (player.room_id, std::cmp::Reverse(player.current_value)));
+            println!("players: {}", players.len());
+            for player in &players {
+                println!("  {}", format_player_row(player));
+            }
+        }
+        Command::ForceCleanup { wallet } => {
+            let signer = signer(&config);
+            let player = load_player(&client, &program_id, &wallet);
+            send(
+                &client,
+                &signer,
+                &[instruction::force_cleanup(
+                    &program_id,
+                    &signer.pubkey(),
+                    player.room_id,
+                    &wallet,
+                )],
+            );
+        }
+        Command::ForceRefund { wallet, charge_fee } => {
+            let signer = signer(&config);
+            let player = load_player(&client, &program_id, &wallet);
+            send(
+                &client,
+                &signer,
+                &[instruction::force_refund(
+                    &program_id,
+                    &signer.pubkey(),
+                    player.room_id,
+                    &wallet,
+                    charge_fee,
+                )],
+            );
+        }
+        Command::Reconcile { room_id, page_size } => {
+            let signer = signer(&config);
+            let signatures = reconcile_room(&client, &program_id, &signer, room_id, page_size)
+                .unwrap_or_else(|e| fail(&format!("reconcile failed: {}", e)));
+            for signature in &signatures {
+                println!("signature: {}", signature);
+            }
+            let room = client
+                .get_account_data(&instruction::room_address(&program_id, room_id))
+                .ok()
+                .and_then(|data| Room::try_from_slice(&data).ok())
+                .unwrap_or_else(|| fail("cannot load room"));
+            println!(
+                "room {}: {} active players, pool {}",
+                room_id,
+                room.active_players,
+                format_sol(room.total_pool)
+            );
+        }
+        Command::WithdrawFees { amount, recipient } => {
+            let signer = signer(&config);
+            let game = load_game(&client, &program_id);
+            // Prowizje leżą na kontach pokoi - najpierw zbiera je CollectRoomFees
+            let rooms: Vec<Room> = fetch_rooms(&client, &program_id)
+                .unwrap_or_else(|e| fail(&format!("RPC error: {}", e)))
+                .into_iter()
+                .filter(|room| room.fee_collected > 0)
+                .collect();
+            let pending: u64 = rooms.iter().map(|room| room.fee_collected).sum();
+            let amount = amount.unwrap_or(game.platform_fee_collected + pending);
+            if amount == 0 {
+                fail("no fees to withdraw");
+            }
+            let mut instructions: Vec<Instruction> = rooms
+                .iter()
+                .map(|room| instruction::collect_room_fees(&program_id, room.room_id))
+                .collect();
+            instructions.push(instruction::withdraw_fees(
+                &program_id,
+                &signer.pubkey(),
+                &recipient.unwrap_or(game.platform_fee_wallet),
+                amount,
+            ));
+            send(&client, &signer, &instructions);
+        }
+    }
+}
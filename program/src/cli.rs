@@ -0,0 +1,567 @@
+// program/src/cli.rs
+use std::str::FromStr;
+
+use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+use thiserror::Error;
+
+use crate::{
+    instruction::MAX_RECONCILE_PAGE_SIZE,
+    solana_io::{GlobalGame, PlayerState},
+};
+
+// Część `solana-io-cli` bez RPC: parsowanie argumentów i formatowanie kont.
+// Binarka (feature `rpc`) tylko wysyła instrukcje z `instruction` i pobiera konta.
+
+pub const USAGE: &str = "usage: solana-io-cli [options] <command>
+
+options:
+  --url <rpc>            RPC endpoint (default: http://127.0.0.1:8899, solana-test-validator)
+  --keypair <path>       signer and fee payer (default: ~/.config/solana/id.json)
+  --program-id <pubkey>  program address (default: $SOLANA_IO_PROGRAM_ID)
+
+commands:
+  init --server <pubkey> --admin <pubkey> --fee-wallet <pubkey> [--room <id>]
+  set-params [--min-stake <sol>] [--max-stake <sol>] [--fee-percent <n>]
+             [--fee-wallet <pubkey>] [--heartbeat-timeout <seconds>]
+             (stake limits are defaults for new rooms, existing rooms are unchanged)
+  room-params <room_id> [--min-stake <sol>] [--max-stake <sol>]
+  show-game
+  show-player <wallet>
+  list-players [--room <id>] [--active]
+  force-cleanup <wallet>
+  force-refund <wallet> [--with-fee]
+  withdraw-fees [--amount <sol>] [--recipient <pubkey>]
+  reconcile <room-id> [--page-size <n>]  (joins, gameplay and cash outs must be paused)";
+
+pub const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+pub const PROGRAM_ID_ENV: &str = "SOLANA_IO_PROGRAM_ID";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CliError {
+    #[error("{0}\n\n{USAGE}")]
+    Usage(String),
+    #[error("invalid value for {flag}: {value}")]
+    InvalidValue { flag: String, value: String },
+}
+
+/// Opcje wspólne dla wszystkich komend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub url: String,
+    /// None - domyślny klucz CLI Solany
+    pub keypair_path: Option<String>,
+    /// None - z `SOLANA_IO_PROGRAM_ID`
+    pub program_id: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Init {
+        server_authority: Pubkey,
+        admin_authority: Pubkey,
+        platform_fee_wallet: Pubkey,
+        room_id: Option<u32>,
+    },
+    SetParams {
+        min_stake: Option<u64>,
+        max_stake: Option<u64>,
+        platform_fee_percent: Option<u8>,
+        platform_fee_wallet: Option<Pubkey>,
+        heartbeat_timeout: Option<i64>,
+    },
+    /// Limity stawek istniejącego pokoju (set-params zmienia tylko domyślne dla nowych)
+    RoomParams {
+        room_id: u32,
+        min_stake: Option<u64>,
+        max_stake: Option<u64>,
+    },
+    ShowGame,
+    ShowPlayer {
+        wallet: Pubkey,
+    },
+    ListPlayers {
+        room_id: Option<u32>,
+        active_only: bool,
+    },
+    ForceCleanup {
+        wallet: Pubkey,
+    },
+    ForceRefund {
+        wallet: Pubkey,
+        charge_fee: bool,
+    },
+    WithdrawFees {
+        /// None - wszystkie zebrane prowizje
+        amount: Option<u64>,
+        /// None - portfel prowizji z konfiguracji gry
+        recipient: Option<Pubkey>,
+    },
+    Reconcile {
+        room_id: u32,
+        page_size: usize,
+    },
+}
+
+fn usage(message: impl Into<String>) -> CliError {
+    CliError::Usage(message.into())
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: &str) -> Result<T, CliError> {
+    value.parse().map_err(|_| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// Kwota w SOL (np. "0.05") na lamporty - bez zaokrągleń przez f64
+pub fn parse_sol(value: &str) -> Option<u64> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > 9
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let fraction: u64 = format!("{:0<9}", fraction).parse().ok()?;
+    whole.checked_mul(LAMPORTS_PER_SOL)?.checked_add(fraction)
+}
+
+/// Lamporty jako SOL bez zbędnych zer ("0.05 SOL")
+pub fn format_sol(lamports: u64) -> String {
+    let whole = lamports / LAMPORTS_PER_SOL;
+    let fraction = lamports % LAMPORTS_PER_SOL;
+    if fraction == 0 {
+        return format!("{} SOL", whole);
+    }
+    let fraction = format!("{:09}", fraction);
+    format!("{}.{} SOL", whole, fraction.trim_end_matches('0'))
+}
+
+/// Flagi komendy w postaci `--nazwa wartość` i `--przełącznik`
+struct Flags<'a> {
+    args: std::slice::Iter<'a, String>,
+    positional: Vec<&'a String>,
+}
+
+impl<'a> Flags<'a> {
+    fn new(args: &'a [String]) -> Self {
+        Self {
+            args: args.iter(),
+            positional: Vec::new(),
+        }
+    }
+
+    /// Następna flaga; argumenty bez `--` trafiają do `positional`
+    fn next_flag(&mut self) -> Option<&'a str> {
+        for arg in self.args.by_ref() {
+            if arg.starts_with("--") {
+                return Some(arg.as_str());
+            }
+            self.positional.push(arg);
+        }
+        None
+    }
+
+    fn value(&mut self, flag: &str) -> Result<&'a str, CliError> {
+        self.args
+            .next()
+            .map(String::as_str)
+            .ok_or_else(|| usage(format!("missing value for {}", flag)))
+    }
+
+    fn parsed<T: FromStr>(&mut self, flag: &str) -> Result<T, CliError> {
+        let value = self.value(flag)?;
+        parse_value(flag, value)
+    }
+
+    fn sol(&mut self, flag: &str) -> Result<u64, CliError> {
+        let value = self.value(flag)?;
+        parse_sol(value).ok_or_else(|| CliError::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+fn required<T>(value: Option<T>, flag: &str) -> Result<T, CliError> {
+    value.ok_or_else(|| usage(format!("missing {}", flag)))
+}
+
+fn single_wallet(flags: &Flags) -> Result<Pubkey, CliError> {
+    match flags.positional.as_slice() {
+        [wallet] => parse_value("wallet", wallet),
+        _ => Err(usage("expected one <wallet> argument")),
+    }
+}
+
+/// Parsuje argumenty wiersza poleceń (bez nazwy programu)
+pub fn parse_args(args: &[String]) -> Result<(Config, Command), CliError> {
+    let mut config = Config {
+        url: DEFAULT_URL.to_string(),
+        keypair_path: None,
+        program_id: None,
+    };
+
+    // Opcje globalne stoją przed komendą
+    let mut rest = args;
+    let name = loop {
+        let (arg, tail) = rest.split_first().ok_or_else(|| usage("missing command"))?;
+        let value = || {
+            tail.first()
+                .map(String::as_str)
+                .ok_or_else(|| usage(format!("missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--url" => config.url = value()?.to_string(),
+            "--keypair" => config.keypair_path = Some(value()?.to_string()),
+            "--program-id" => config.program_id = Some(parse_value(arg, value()?)?),
+            _ => break arg.as_str(),
+        }
+        rest = &tail[1..];
+    };
+
+    let mut flags = Flags::new(&rest[1..]);
+    let command = match name {
+        "init" => {
+            let (mut server, mut admin, mut wallet, mut room_id) = (None, None, None, None);
+            while let Some(flag) = flags.next_flag() {
+                match flag {
+                    "--server" => server = Some(flags.parsed(flag)?),
+                    "--admin" => admin = Some(flags.parsed(flag)?),
+                    "--fee-wallet" => wallet = Some(flags.parsed(flag)?),
+                    "--room" => room_id = Some(flags.parsed(flag)?),
+                    _ => return Err(usage(format!("unknown option {}", flag))),
+                }
+            }
+            Command::Init {
+                server_authority: required(server, "--server")?,
+                admin_authority: required(admin, "--admin")?,
+                platform_fee_wallet: required(wallet, "--fee-wallet")?,
+                room_id,
+            }
+        }
+        "set-params" => {
+            let (mut min_stake, mut max_stake, mut fee_percent) = (None, None, None);
+            let (mut fee_wallet, mut heartbeat_timeout) = (None, None);
+            while let Some(flag) = flags.next_flag() {
+                match flag {
+                    "--min-stake" => min_stake = Some(flags.sol(flag)?),
+                    "--max-stake" => max_stake = Some(flags.sol(flag)?),
+                    "--fee-percent" => fee_percent = Some(flags.parsed(flag)?),
+                    "--fee-wallet" => fee_wallet = Some(flags.parsed(flag)?),
+                    "--heartbeat-timeout" => heartbeat_timeout = Some(flags.parsed(flag)?),
+                    _ => return Err(usage(format!("unknown option {}", flag))),
+                }
+            }
+            Command::SetParams {
+                min_stake,
+                max_stake,
+                platform_fee_percent: fee_percent,
+                platform_fee_wallet: fee_wallet,
+                heartbeat_timeout,
+            }
+        }
+        "room-params" => {
+            let (mut min_stake, mut max_stake) = (None, None);
+            while let Some(flag) = flags.next_flag() {
+                match flag {
+                    "--min-stake" => min_stake = Some(flags.sol(flag)?),
+                    "--max-stake" => max_stake = Some(flags.sol(flag)?),
+                    _ => return Err(usage(format!("unknown option {}", flag))),
+                }
+            }
+            let room_id = match flags.positional.as_slice() {
+                [room_id] => parse_value("room_id", room_id)?,
+                _ => return Err(usage("expected one <room_id> argument")),
+            };
+            Command::RoomParams {
+                room_id,
+                min_stake,
+                max_stake,
+            }
+        }
+        "show-game" => {
+            if let Some(flag) = flags.next_flag() {
+                return Err(usage(format!("unknown option {}", flag)));
+            }
+            Command::ShowGame
+        }
+        "show-player" => {
+            if let Some(flag) = flags.next_flag() {
+                return Err(usage(format!("unknown option {}", flag)));
+            }
+            Command::ShowPlayer {
+                wallet: single_wallet(&flags)?,
+            }
+        }
+        "list-players" => {
+            let (mut room_id, mut active_only) = (None, false);
+            while let Some(flag) = flags.next_flag() {
+                match flag {
+                    "--room" => room_id = Some(flags.parsed(flag)?),
+                    "--active" => active_only = true,
+                    _ => return Err(usage(format!("unknown option {}", flag))),
+                }
+            }
+            Command::ListPlayers {
+                room_id,
+                active_only,
+            }
+        }
+        "force-cleanup" => {
+            if let Some(flag) = flags.next_flag() {
+                return Err(usage(format!("unknown option {}", flag)));
+            }
+            Command::ForceCleanup {
+                wallet: single_wallet(&flags)?,
+            }
+        }
+        "force-refund" => {
+            let mut charge_fee = false;
+            while let Some(flag) = flags.next_flag() {
+                match flag {
+                    "--with-fee" => charge_fee = true,
+                    _ => return Err(usage(format!("unknown option {}", flag))),
+                }
+            }
+            Command::ForceRefund {
+                wallet: single_wallet(&flags)?,
+                charge_fee,
+            }
+        }
+        "withdraw-fees" => {
+            let (mut amount, mut recipient) = (None, None);
+            while let Some(flag) = flags.next_flag() {
+                match flag {
+                    "--amount" => amount = Some(flags.sol(flag)?),
+                    "--recipient" => recipient = Some(flags.parsed(flag)?),
+                    _ => return Err(usage(format!("unknown option {}", flag))),
+                }
+            }
+            Command::WithdrawFees { amount, recipient }
+        }
+        "reconcile" => {
+            let mut page_size = MAX_RECONCILE_PAGE_SIZE;
+            while let Some(flag) = flags.next_flag() {
+                match flag {
+                    "--page-size" => page_size = flags.parsed(flag)?,
+                    _ => return Err(usage(format!("unknown option {}", flag))),
+                }
+            }
+            let room_id = match flags.positional.as_slice() {
+                [room_id] => parse_value("room-id", room_id)?,
+                _ => return Err(usage("expected one <room-id> argument")),
+            };
+            if !(1..=MAX_RECONCILE_PAGE_SIZE).contains(&page_size) {
+                return Err(CliError::InvalidValue {
+                    flag: "--page-size".to_string(),
+                    value: page_size.to_string(),
+                });
+            }
+            Command::Reconcile { room_id, page_size }
+        }
+        _ => return Err(usage(format!("unknown command {}", name))),
+    };
+
+    let takes_argument = matches!(
+        command,
+        Command::ShowPlayer { .. }
+            | Command::ForceCleanup { .. }
+            | Command::RoomParams { .. }
+            | Command::ForceRefund { .. }
+            | Command::Reconcile { .. }
+    );
+    if !takes_argument && !flags.positional.is_empty() {
+        return Err(usage(format!(
+            "unexpected argument {}",
+            flags.positional[0]
+        )));
+    }
+    Ok((config, command))
+}
+
+fn format_option(key: Option<Pubkey>) -> String {
+    key.map_or_else(|| "-".to_string(), |key| key.to_string())
+}
+
+/// Konfiguracja gry i saldo skarbca prowizji (show-game)
+pub fn format_game(game: &GlobalGame, fee_vault_lamports: u64) -> String {
+    let lines = [
+        format!("server authority:      {}", game.server_authority),
+        format!("admin authority:       {}", game.admin_authority),
+        format!(
+            "pending server:        {}",
+            format_option(game.pending_server_authority)
+        ),
+        format!(
+            "pending admin:         {}",
+            format_option(game.pending_admin_authority)
+        ),
+        format!("platform fee wallet:   {}", game.platform_fee_wallet),
+        format!("platform fee:          {}%", game.platform_fee_percent),
+        format!("default min stake:     {}", format_sol(game.min_stake)),
+        format!("default max stake:     {}", format_sol(game.max_stake)),
+        format!(
+            "fees collected:        {}",
+            format_sol(game.platform_fee_collected)
+        ),
+        format!(
+            "fees withdrawn:        {}",
+            format_sol(game.total_fees_withdrawn)
+        ),
+        format!("fee vault balance:     {}", format_sol(fee_vault_lamports)),
+        format!(
+            "paused:                joins={} gameplay={} cashouts={}",
+            game.paused_joins, game.paused_gameplay, game.paused_cashouts
+        ),
+        format!(
+            "last heartbeat:        {} (timeout {}s)",
+            game.last_heartbeat, game.heartbeat_timeout
+        ),
+        format!("created at:            {}", game.created_at),
+    ];
+    lines.join("\n")
+}
+
+/// Stan gracza (show-player)
+pub fn format_player(player_state: &PlayerState) -> String {
+    let lines = [
+        format!("wallet:          {}", player_state.pubkey),
+        format!("room:            {}", player_state.room_id),
+        format!("active:          {}", player_state.is_active),
+        format!(
+            "current value:   {}",
+            format_sol(player_state.current_value)
+        ),
+        format!("session stake:   {}", format_sol(player_state.session_stake)),
+        format!("total staked:    {}", format_sol(player_state.stake_amount)),
+        format!("total earned:    {}", format_sol(player_state.total_earned)),
+        format!("joined at:       {}", player_state.joined_at),
+        format!("last cash out:   {}", player_state.last_cashout),
+        format!("cash out nonce:  {}", player_state.cashout_nonce),
+    ];
+    lines.join("\n")
+}
+
+/// Jedna linia na gracza (list-players)
+pub fn format_player_row(player_state: &PlayerState) -> String {
+    format!(
+        "{} room={} active={} value={}",
+        player_state.pubkey,
+        player_state.room_id,
+        player_state.is_active,
+        format_sol(player_state.current_value)
+    )
+}
+
+/// Odczyt stanów graczy i przebieg Reconcile przez RPC walidatora
+#[cfg(feature = "rpc")]
+pub mod rpc {
+    use borsh::BorshDeserialize;
+    use solana_client::{
+        client_error::ClientError,
+        rpc_client::RpcClient,
+        rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+        rpc_filter::{Memcmp, RpcFilterType},
+    };
+    use solana_program::pubkey::Pubkey;
+    use solana_sdk::{
+        signature::{Keypair, Signature},
+        signer::Signer,
+        transaction::Transaction,
+    };
+
+    use crate::{
+        instruction::reconcile_pages,
+        solana_io::{PlayerState, Room},
+    };
+
+    /// Wszystkie stany graczy programu (opcjonalnie tylko z jednego pokoju)
+    pub fn fetch_player_states(
+        client: &RpcClient,
+        program_id: &Pubkey,
+        room_id: Option<u32>,
+    ) -> Result<Vec<PlayerState>, Box<ClientError>> {
+        // Stany graczy to jedyne konta programu o rozmiarze PlayerState::SIZE
+        let mut filters = vec![RpcFilterType::DataSize(PlayerState::SIZE as u64)];
+        if let Some(room_id) = room_id {
+            filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                PlayerState::ROOM_ID_OFFSET,
+                room_id.to_le_bytes().to_vec(),
+            )));
+        }
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig::default(),
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = client.get_program_accounts_with_config(program_id, config)?;
+        Ok(accounts
+            .iter()
+            .filter_map(|(_, account)| PlayerState::try_from_slice(&account.data).ok())
+            .collect())
+    }
+
+    /// Wszystkie pokoje programu
+    pub fn fetch_rooms(
+        client: &RpcClient,
+        program_id: &Pubkey,
+    ) -> Result<Vec<Room>, Box<ClientError>> {
+        // Pokoje to jedyne konta programu o rozmiarze Room::SIZE
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::DataSize(Room::SIZE as u64)]),
+            account_config: RpcAccountInfoConfig::default(),
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = client.get_program_accounts_with_config(program_id, config)?;
+        Ok(accounts
+            .iter()
+            .filter_map(|(_, account)| Room::try_from_slice(&account.data).ok())
+            .filter(|room| room.is_initialized)
+            .collect())
+    }
+
+    /// Uzgadnia liczniki pokoju ze wszystkich jego stanów graczy - strona po stronie,
+    /// każda w osobnej transakcji. `admin` jest jednocześnie płatnikiem.
+    pub fn reconcile_room(
+        client: &RpcClient,
+        program_id: &Pubkey,
+        admin: &Keypair,
+        room_id: u32,
+        page_size: usize,
+    ) -> Result<Vec<Signature>, Box<ClientError>> {
+        let players: Vec<Pubkey> = fetch_player_states(client, program_id, Some(room_id))?
+            .iter()
+            .map(|player_state| player_state.pubkey)
+            .collect();
+        let pages = reconcile_pages(
+            program_id,
+            &admin.pubkey(),
+            &admin.pubkey(),
+            room_id,
+            &players,
+            page_size,
+        );
+
+        let mut signatures = Vec::with_capacity(pages.len());
+        for page in pages {
+            let blockhash = client.get_latest_blockhash()?;
+            let transaction = Transaction::new_signed_with_payer(
+                &[page],
+                Some(&admin.pubkey()),
+                &[admin],
+                blockhash,
+            );
+            signatures.push(client.send_and_confirm_transaction(&transaction)?);
+        }
+        Ok(signatures)
+    }
+}
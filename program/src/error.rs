@@ -0,0 +1,259 @@
+// program/src/error.rs
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+/// Błędy programu - kody są stabilne (ProgramError::Custom), klienci mapują je na komunikaty.
+/// Nowe warianty dodajemy TYLKO na końcu, nigdy nie zmieniamy istniejących numerów.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Error, FromPrimitive)]
+pub enum SolanaIoError {
+    /// Globalna gra już istnieje
+    #[error("Game is already initialized")]
+    GameAlreadyInitialized = 0,
+
+    /// Konto gry nie jest PDA `global_game`
+    #[error("Game account is not the global game PDA")]
+    InvalidGameAccount = 1,
+
+    /// Konto stanu gracza nie jest PDA `player_state` tego gracza
+    #[error("Player state account is not the player state PDA")]
+    InvalidPlayerStateAccount = 2,
+
+    /// Stawka poniżej min_stake
+    #[error("Stake is below the minimum stake")]
+    StakeBelowMinimum = 3,
+
+    /// Stawka powyżej max_stake
+    #[error("Stake is above the maximum stake")]
+    StakeAboveMaximum = 4,
+
+    /// Gracz już jest w grze
+    #[error("Player is already active in the game")]
+    PlayerAlreadyActive = 5,
+
+    /// Podpisujący nie jest server authority zapisanym w grze
+    #[error("Signer is not the game server authority")]
+    UnauthorizedServerAuthority = 6,
+
+    /// Gracz nie jest aktywny
+    #[error("Player is not active")]
+    PlayerNotActive = 7,
+
+    /// Zjedzony gracz nie jest aktywny
+    #[error("Eaten player is not active")]
+    EatenPlayerNotActive = 8,
+
+    /// Gracz nie ma nic do wypłaty
+    #[error("Player has no value to cash out")]
+    NothingToCashOut = 9,
+
+    /// Przekazane konto prowizji nie jest portfelem platformy
+    #[error("Platform fee account does not match the platform wallet")]
+    InvalidPlatformFeeAccount = 10,
+
+    /// Prowizja powyżej dopuszczalnego maksimum
+    #[error("Platform fee percent is above the allowed maximum")]
+    FeePercentTooHigh = 11,
+
+    /// min_stake większe niż max_stake
+    #[error("Minimum stake is greater than maximum stake")]
+    InvalidStakeRange = 12,
+
+    /// Konto gracza nie zgadza się z kluczem z instrukcji
+    #[error("Player account does not match the instruction argument")]
+    PlayerAccountMismatch = 13,
+
+    /// eaten_value z instrukcji nie zgadza się z current_value zjedzonego gracza
+    #[error("Eaten value does not match the eaten player's on-chain value")]
+    EatenValueMismatch = 14,
+
+    /// Gracz i zjedzony gracz to ta sama osoba
+    #[error("Player cannot eat itself")]
+    SelfEatNotAllowed = 15,
+
+    /// Przepełnienie arytmetyczne
+    #[error("Arithmetic overflow")]
+    MathOverflow = 16,
+
+    /// Konto nie należy do oczekiwanego programu
+    #[error("Account is not owned by the expected program")]
+    InvalidAccountOwner = 17,
+
+    /// Konto musi być oznaczone jako writable
+    #[error("Account must be writable")]
+    AccountNotWritable = 18,
+
+    /// To samo konto przekazane więcej niż raz
+    #[error("The same account was passed more than once")]
+    DuplicateAccount = 19,
+
+    /// Przekazany system program nie jest System Program
+    #[error("Account is not the system program")]
+    InvalidSystemProgram = 20,
+
+    /// Przekazane konto nie jest oczekiwanym sysvarem
+    #[error("Account is not the expected sysvar")]
+    InvalidSysvar = 21,
+
+    /// Konto skarbca prowizji nie jest PDA `fee_vault`
+    #[error("Fee vault account is not the fee vault PDA")]
+    InvalidFeeVaultAccount = 22,
+
+    /// Próba wypłaty większej kwoty niż zebrane prowizje
+    #[error("Withdrawal exceeds accrued platform fees")]
+    InsufficientAccruedFees = 23,
+
+    /// Podpisujący nie jest admin authority gry
+    #[error("Signer is not the admin authority")]
+    UnauthorizedAdminAuthority = 24,
+
+    /// Brak oczekującej propozycji dla tej roli
+    #[error("No pending authority proposal for this role")]
+    NoPendingAuthority = 25,
+
+    /// Podpisujący nie jest kluczem zaproponowanym dla tej roli
+    #[error("Signer is not the proposed authority")]
+    PendingAuthorityMismatch = 26,
+
+    /// Konto multisig nie jest PDA multisig dla roli albo nie jest zainicjalizowane
+    #[error("Multisig account is not the multisig PDA for this role")]
+    InvalidMultisigAccount = 27,
+
+    /// Konto multisig dla roli już istnieje
+    #[error("Multisig account already exists")]
+    MultisigAlreadyExists = 28,
+
+    /// Nieprawidłowa lista członków lub próg (pusty, zbyt długi, duplikaty, próg 0 lub > N)
+    #[error("Invalid multisig signers or threshold")]
+    InvalidMultisigConfig = 29,
+
+    /// Za mało podpisów członków multisig
+    #[error("Not enough multisig signers")]
+    MultisigThresholdNotMet = 30,
+
+    /// Instrukcja wstrzymana przez SetPaused. Klient rozpoznaje ten kod
+    /// (0x1f) i pokazuje komunikat o przerwie technicznej.
+    #[error("Program is paused for maintenance")]
+    ProgramPaused = 31,
+
+    /// Serwer nie wysłał Heartbeat w czasie `heartbeat_timeout` - akcje serwera są zablokowane
+    #[error("Server heartbeat is stale")]
+    ServerStale = 32,
+
+    /// EmergencyWithdraw jest dostępny tylko gdy serwer przestał wysyłać Heartbeat
+    #[error("Server is alive, emergency withdraw is not available")]
+    ServerNotStale = 33,
+
+    /// Timeout heartbeatu musi być dodatni
+    #[error("Heartbeat timeout must be positive")]
+    InvalidHeartbeatTimeout = 34,
+
+    /// Brak instrukcji ed25519 przed CashOut albo bilet nie jest podpisany przez server authority
+    #[error("Cash out ticket is missing or not signed by the server authority")]
+    InvalidCashOutTicket = 35,
+
+    /// Bilet wystawiony dla innego gracza albo na inną kwotę niż wartość on-chain
+    #[error("Cash out ticket does not match the player state")]
+    CashOutTicketMismatch = 36,
+
+    /// Bilet po terminie ważności
+    #[error("Cash out ticket has expired")]
+    CashOutTicketExpired = 37,
+
+    /// Nonce biletu nie jest kolejnym nonce gracza (powtórzony bilet)
+    #[error("Cash out ticket nonce does not match")]
+    InvalidTicketNonce = 38,
+
+    /// Konto pokoju nie jest PDA pokoju albo nie jest zainicjalizowane
+    #[error("Invalid room account")]
+    InvalidRoomAccount = 39,
+
+    /// Pokój o tym id już istnieje
+    #[error("Room already exists")]
+    RoomAlreadyExists = 40,
+
+    /// Gracz gra w innym pokoju niż przekazany
+    #[error("Player is not in this room")]
+    PlayerRoomMismatch = 41,
+
+    /// BatchUpdatePlayerValues bez zdarzeń
+    #[error("Eat event batch is empty")]
+    EmptyEatBatch = 42,
+
+    /// Zamykany stan gracza ma jeszcze wartość do wypłaty
+    #[error("Player state still holds value")]
+    PlayerStateNotEmpty = 43,
+
+    /// Saldo pokoju lub skarbca nie pokrywa czynszu i zapisanych sald
+    #[error("Account lamports do not cover rent and recorded balances")]
+    PoolInsolvent = 44,
+
+    /// Reconcile wymaga wstrzymanych dołączeń, rozgrywki i wypłat
+    #[error("Joins, gameplay and cash outs must be paused")]
+    GameNotPaused = 45,
+
+    /// Konto uzgodnienia nie jest PDA uzgodnienia pokoju
+    #[error("Invalid reconciliation account")]
+    InvalidReconciliationAccount = 46,
+
+    /// Strona Reconcile bez rozpoczętego uzgodnienia (brak `start`)
+    #[error("Reconciliation has not been started")]
+    ReconciliationNotStarted = 47,
+
+    /// Stany graczy nie są posortowane rosnąco albo były już policzone
+    #[error("Player states must be sorted and not yet counted")]
+    ReconcilePlayersNotSorted = 48,
+
+    /// Strona Reconcile z większą liczbą graczy niż `MAX_RECONCILE_PAGE_SIZE`
+    #[error("Too many player states in one Reconcile page")]
+    ReconcilePageTooLarge = 49,
+
+    /// Po częściowej wypłacie wartość gracza spadłaby poniżej minimalnej stawki pokoju
+    #[error("Partial cash out must leave at least the room minimum stake")]
+    PartialCashOutTooLarge = 50,
+
+    /// Dopłata do stawki musi być dodatnia
+    #[error("Top-up amount must be positive")]
+    InvalidTopUpAmount = 51,
+}
+
+impl From<SolanaIoError> for ProgramError {
+    fn from(e: SolanaIoError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for SolanaIoError {
+    fn type_of() -> &'static str {
+        "SolanaIoError"
+    }
+}
+
+impl PrintProgramError for SolanaIoError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
+
+impl SolanaIoError {
+    /// Dekoduje kod z `ProgramError::Custom` (np. z logów transakcji)
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::from_u32(code)
+    }
+
+    /// Wyciąga błąd programu z `ProgramError`, jeśli to nasz kod
+    pub fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => Self::from_code(*code),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// program/src/events.rs
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+// Zdarzenia programu w logach transakcji. Procesory emitują je przez
+// `sol_log_data` jako borsh - w logach pojawiają się jako "Program data: <base64>".
+// `msg!` zostaje dla ludzi, indeksery i serwer czytają tylko zdarzenia.
+//
+// Nowe warianty dopisujemy wyłącznie na końcu - indeks wariantu jest częścią formatu.
+
+/// Zdarzenie zapisane w logach transakcji
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum SolanaIoEvent {
+    /// InitializeGame
+    GameInitialized {
+        server_authority: Pubkey,
+        admin_authority: Pubkey,
+        platform_fee_wallet: Pubkey,
+    },
+
+    /// JoinGame - `current_value` już po doliczeniu stawki
+    PlayerJoined {
+        player: Pubkey,
+        room_id: u32,
+        stake_amount: u64,
+        current_value: u64,
+        rejoined: bool,
+    },
+
+    /// UpdatePlayerValue lub jedno zdarzenie z BatchUpdatePlayerValues
+    PlayerEaten {
+        room_id: u32,
+        player: Pubkey,
+        eaten_player: Pubkey,
+        eaten_value: u64,
+        new_value: u64,
+    },
+
+    /// CashOut lub EmergencyWithdraw (`emergency`, bez prowizji)
+    PlayerCashedOut {
+        player: Pubkey,
+        room_id: u32,
+        value: u64,
+        payout: u64,
+        fee: u64,
+        emergency: bool,
+    },
+
+    /// ForceCleanup - wartość gracza przepada bez wypłaty
+    PlayerForceCleaned {
+        player: Pubkey,
+        room_id: u32,
+        lost_value: u64,
+    },
+
+    /// UpdateGameParams - parametry po zmianie
+    ParamsUpdated {
+        min_stake: u64,
+        max_stake: u64,
+        platform_fee_percent: u8,
+        platform_fee_wallet: Pubkey,
+        heartbeat_timeout: i64,
+    },
+
+    /// ClosePlayerState lub CashOut z `close` - czynsz konta wrócił do gracza
+    PlayerStateClosed { player: Pubkey, rent_refunded: u64 },
+
+    /// Reconcile z `finish` - liczniki pokoju przed i po uzgodnieniu
+    RoomReconciled {
+        room_id: u32,
+        players_scanned: u32,
+        previous_active_players: u32,
+        active_players: u32,
+        previous_total_pool: u64,
+        total_pool: u64,
+    },
+
+    /// ForceRefund - serwer zwrócił wartość gracza po awarii (`fee` 0 bez prowizji)
+    PlayerRefunded {
+        player: Pubkey,
+        room_id: u32,
+        value: u64,
+        payout: u64,
+        fee: u64,
+    },
+
+    /// PartialCashOut - gracz wypłacił część wartości i gra dalej
+    PlayerPartiallyCashedOut {
+        player: Pubkey,
+        room_id: u32,
+        amount: u64,
+        payout: u64,
+        fee: u64,
+        remaining_value: u64,
+    },
+
+    /// TopUp - gracz dopłacił do stawki, serwer zwiększa jego masę
+    PlayerToppedUp {
+        player: Pubkey,
+        room_id: u32,
+        amount: u64,
+        session_stake: u64,
+        current_value: u64,
+    },
+}
+
+impl SolanaIoEvent {
+    /// Zapisuje zdarzenie w logach transakcji
+    pub fn emit(&self) {
+        // Serializacja borsh do Vec nie może się nie udać
+        let data = self.try_to_vec().expect("event serialization");
+        sol_log_data(&[&data]);
+    }
+
+    /// Dekoduje zdarzenie z bajtów `sol_log_data`
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        Self::try_from_slice(data).ok()
+    }
+}
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Dekoduje jedną linię "Program data: ..." - bez sprawdzania, który program ją zapisał
+pub fn decode_log_line(line: &str) -> Option<SolanaIoEvent> {
+    let encoded = line.strip_prefix(PROGRAM_DATA_PREFIX)?;
+    // Emitujemy jedno pole, więc w linii jest jeden blok base64
+    let data = STANDARD.decode(encoded.split(' ').next()?).ok()?;
+    SolanaIoEvent::decode(&data)
+}
+
+/// Zdarzenia programu `program_id` z logów transakcji, w kolejności emisji.
+/// Linie "Program data" innych programów (także wywoływanych przez CPI) są pomijane.
+/// Logi nieudanej transakcji mogą zawierać zdarzenia, które zostały wycofane -
+/// podajemy tylko logi transakcji zakończonych sukcesem.
+pub fn parse_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<SolanaIoEvent> {
+    let program = program_id.to_string();
+    // Stos wywołań: "Program X invoke [n]" ... "Program X success" / "Program X failed: ..."
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        let line = line.as_ref();
+        if line.starts_with(PROGRAM_DATA_PREFIX) {
+            if stack.last() == Some(&program.as_str()) {
+                events.extend(decode_log_line(line));
+            }
+            continue;
+        }
+
+        let mut words = line.split(' ');
+        if words.next() != Some("Program") {
+            continue;
+        }
+        match (words.next(), words.next()) {
+            (Some(id), Some("invoke")) => stack.push(id),
+            (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
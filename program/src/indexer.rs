@@ -0,0 +1,475 @@
+// program/src/indexer.rs
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::events::{parse_logs, SolanaIoEvent};
+
+// Indekser off-chain. `PlayerState` trzyma tylko bieżące wartości, więc historię
+// (kto kogo zjadł, wynik portfela, prowizje dziennie) odtwarzamy ze zdarzeń
+// z logów transakcji. Projekcja jest w pamięci i da się ją zbudować od zera
+// z pliku z nagranymi logami albo z RPC walidatora (feature `rpc`).
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Logi jednej udanej transakcji programu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum LogFileError {
+    #[error("line {0}: expected `tx <signature> <slot> [<block_time>|-]`")]
+    InvalidHeader(usize),
+    #[error("line {0}: log line before the first `tx` header")]
+    MissingHeader(usize),
+}
+
+/// Czyta plik z nagranymi logami. Każda transakcja zaczyna się nagłówkiem
+/// `tx <sygnatura> <slot> [<block_time>|-]`, po którym idą jej linie logów.
+/// Puste linie i linie zaczynające się od `#` są pomijane.
+pub fn read_log_file(input: &str) -> Result<Vec<IndexedTransaction>, LogFileError> {
+    let mut transactions: Vec<IndexedTransaction> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let number = i + 1;
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix("tx ") {
+            let mut fields = header.split_whitespace();
+            let signature = fields.next().ok_or(LogFileError::InvalidHeader(number))?;
+            let slot = fields
+                .next()
+                .and_then(|slot| slot.parse().ok())
+                .ok_or(LogFileError::InvalidHeader(number))?;
+            let block_time = match fields.next() {
+                None | Some("-") => None,
+                Some(time) => Some(
+                    time.parse()
+                        .map_err(|_| LogFileError::InvalidHeader(number))?,
+                ),
+            };
+            if fields.next().is_some() {
+                return Err(LogFileError::InvalidHeader(number));
+            }
+            transactions.push(IndexedTransaction {
+                signature: signature.to_string(),
+                slot,
+                block_time,
+                logs: Vec::new(),
+            });
+            continue;
+        }
+
+        let transaction = transactions
+            .last_mut()
+            .ok_or(LogFileError::MissingHeader(number))?;
+        transaction.logs.push(line.to_string());
+    }
+    Ok(transactions)
+}
+
+/// Zapisuje transakcje w formacie `read_log_file`
+pub fn write_log_file(transactions: &[IndexedTransaction]) -> String {
+    let mut output = String::new();
+    for transaction in transactions {
+        let block_time = transaction
+            .block_time
+            .map_or_else(|| "-".to_string(), |time| time.to_string());
+        output.push_str(&format!(
+            "tx {} {} {}\n",
+            transaction.signature, transaction.slot, block_time
+        ));
+        for line in &transaction.logs {
+            output.push_str(line);
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Konfiguracja gry widziana w zdarzeniach
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameInfo {
+    pub server_authority: Pubkey,
+    pub admin_authority: Pubkey,
+    pub platform_fee_wallet: Pubkey,
+    /// Ostatnie UpdateGameParams - None, dopóki nie było zmiany
+    pub platform_fee_percent: Option<u8>,
+    pub min_stake: Option<u64>,
+    pub max_stake: Option<u64>,
+}
+
+/// Podsumowanie portfela ze wszystkich sesji
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub sessions: u32,
+    pub total_staked: u64,
+    pub total_payout: u64,
+    pub fees_paid: u64,
+    pub kills: u32,
+    pub deaths: u32,
+    pub force_cleanups: u32,
+    pub refunds: u32,
+    pub partial_cashouts: u32,
+}
+
+impl PlayerStats {
+    /// Wynik portfela: wypłaty minus wpłacone stawki
+    pub fn pnl(&self) -> i128 {
+        self.total_payout as i128 - self.total_staked as i128
+    }
+}
+
+/// Jak zakończyła się sesja
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEnd {
+    CashedOut { value: u64, payout: u64, fee: u64 },
+    EmergencyWithdraw { value: u64 },
+    Eaten { by: Pubkey, value: u64 },
+    ForceCleaned { lost_value: u64 },
+    Refunded { value: u64, payout: u64, fee: u64 },
+}
+
+/// Od dołączenia do opuszczenia pokoju
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub player: Pubkey,
+    pub room_id: u32,
+    pub stake_amount: u64,
+    pub kills: u32,
+    /// Suma częściowych wypłat w trakcie sesji (wypłaty + prowizje)
+    pub partial_cashouts: u64,
+    pub started_signature: String,
+    pub started_slot: u64,
+    pub ended_signature: Option<String>,
+    pub end: Option<SessionEnd>,
+}
+
+/// Jedno zjedzenie
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kill {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub room_id: u32,
+    pub player: Pubkey,
+    pub eaten_player: Pubkey,
+    pub eaten_value: u64,
+}
+
+/// Projekcja zdarzeń programu w pamięci
+#[derive(Debug, Default)]
+pub struct Projection {
+    pub game: Option<GameInfo>,
+    pub players: HashMap<Pubkey, PlayerStats>,
+    pub sessions: Vec<Session>,
+    pub kills: Vec<Kill>,
+    /// Prowizje według dnia (UTC, dni od epoki unixowej)
+    pub fees_by_day: BTreeMap<i64, u64>,
+    /// Wszystkie prowizje, także z transakcji bez `block_time`
+    pub total_fees: u64,
+    /// Indeks otwartej sesji gracza w `sessions`
+    open_sessions: HashMap<Pubkey, usize>,
+    seen_signatures: HashSet<String>,
+}
+
+impl Projection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stosuje zdarzenia programu z transakcji. Transakcje już widziane są
+    /// pomijane, więc ten sam plik czy zakres RPC można podać ponownie.
+    /// Zwraca liczbę zastosowanych zdarzeń.
+    pub fn apply_transaction(
+        &mut self,
+        program_id: &Pubkey,
+        transaction: &IndexedTransaction,
+    ) -> usize {
+        if !self.seen_signatures.insert(transaction.signature.clone()) {
+            return 0;
+        }
+        let events = parse_logs(program_id, &transaction.logs);
+        for event in &events {
+            self.apply_event(transaction, event);
+        }
+        events.len()
+    }
+
+    /// Stosuje transakcje po kolei (najstarsze pierwsze)
+    pub fn apply_all(&mut self, program_id: &Pubkey, transactions: &[IndexedTransaction]) -> usize {
+        transactions
+            .iter()
+            .map(|transaction| self.apply_transaction(program_id, transaction))
+            .sum()
+    }
+
+    /// Otwarta sesja gracza
+    pub fn open_session(&self, player: &Pubkey) -> Option<&Session> {
+        self.open_sessions.get(player).map(|&i| &self.sessions[i])
+    }
+
+    fn end_session(&mut self, player: &Pubkey, signature: &str, end: SessionEnd) {
+        // Sesja rozpoczęta przed początkiem indeksowania nie jest znana - pomijamy
+        if let Some(i) = self.open_sessions.remove(player) {
+            self.sessions[i].ended_signature = Some(signature.to_string());
+            self.sessions[i].end = Some(end);
+        }
+    }
+
+    fn record_payout(
+        &mut self,
+        transaction: &IndexedTransaction,
+        player: Pubkey,
+        payout: u64,
+        fee: u64,
+    ) -> &mut PlayerStats {
+        self.total_fees += fee;
+        if let Some(time) = transaction.block_time {
+            if fee > 0 {
+                *self
+                    .fees_by_day
+                    .entry(time.div_euclid(SECONDS_PER_DAY))
+                    .or_default() += fee;
+            }
+        }
+        let stats = self.players.entry(player).or_default();
+        stats.total_payout += payout;
+        stats.fees_paid += fee;
+        stats
+    }
+
+    fn apply_event(&mut self, transaction: &IndexedTransaction, event: &SolanaIoEvent) {
+        match *event {
+            SolanaIoEvent::GameInitialized {
+                server_authority,
+                admin_authority,
+                platform_fee_wallet,
+            } => {
+                self.game = Some(GameInfo {
+                    server_authority,
+                    admin_authority,
+                    platform_fee_wallet,
+                    platform_fee_percent: None,
+                    min_stake: None,
+                    max_stake: None,
+                });
+            }
+            SolanaIoEvent::PlayerJoined {
+                player,
+                room_id,
+                stake_amount,
+                ..
+            } => {
+                let stats = self.players.entry(player).or_default();
+                stats.sessions += 1;
+                stats.total_staked += stake_amount;
+                self.open_sessions.insert(player, self.sessions.len());
+                self.sessions.push(Session {
+                    player,
+                    room_id,
+                    stake_amount,
+                    kills: 0,
+                    partial_cashouts: 0,
+                    started_signature: transaction.signature.clone(),
+                    started_slot: transaction.slot,
+                    ended_signature: None,
+                    end: None,
+                });
+            }
+            SolanaIoEvent::PlayerEaten {
+                room_id,
+                player,
+                eaten_player,
+                eaten_value,
+                ..
+            } => {
+                self.players.entry(player).or_default().kills += 1;
+                self.players.entry(eaten_player).or_default().deaths += 1;
+                if let Some(&i) = self.open_sessions.get(&player) {
+                    self.sessions[i].kills += 1;
+                }
+                self.end_session(
+                    &eaten_player,
+                    &transaction.signature,
+                    SessionEnd::Eaten {
+                        by: player,
+                        value: eaten_value,
+                    },
+                );
+                self.kills.push(Kill {
+                    signature: transaction.signature.clone(),
+                    slot: transaction.slot,
+                    block_time: transaction.block_time,
+                    room_id,
+                    player,
+                    eaten_player,
+                    eaten_value,
+                });
+            }
+            SolanaIoEvent::PlayerCashedOut {
+                player,
+                value,
+                payout,
+                fee,
+                emergency,
+                ..
+            } => {
+                self.record_payout(transaction, player, payout, fee);
+                let end = if emergency {
+                    SessionEnd::EmergencyWithdraw { value }
+                } else {
+                    SessionEnd::CashedOut { value, payout, fee }
+                };
+                self.end_session(&player, &transaction.signature, end);
+            }
+            SolanaIoEvent::PlayerForceCleaned {
+                player, lost_value, ..
+            } => {
+                self.players.entry(player).or_default().force_cleanups += 1;
+                self.end_session(
+                    &player,
+                    &transaction.signature,
+                    SessionEnd::ForceCleaned { lost_value },
+                );
+            }
+            SolanaIoEvent::PlayerRefunded {
+                player,
+                value,
+                payout,
+                fee,
+                ..
+            } => {
+                self.record_payout(transaction, player, payout, fee).refunds += 1;
+                self.end_session(
+                    &player,
+                    &transaction.signature,
+                    SessionEnd::Refunded { value, payout, fee },
+                );
+            }
+            SolanaIoEvent::PlayerPartiallyCashedOut {
+                player,
+                amount,
+                payout,
+                fee,
+                ..
+            } => {
+                self.record_payout(transaction, player, payout, fee).partial_cashouts += 1;
+                if let Some(&i) = self.open_sessions.get(&player) {
+                    self.sessions[i].partial_cashouts += amount;
+                }
+            }
+            SolanaIoEvent::PlayerToppedUp { player, amount, .. } => {
+                self.players.entry(player).or_default().total_staked += amount;
+                if let Some(&i) = self.open_sessions.get(&player) {
+                    self.sessions[i].stake_amount += amount;
+                }
+            }
+            SolanaIoEvent::ParamsUpdated {
+                min_stake,
+                max_stake,
+                platform_fee_percent,
+                platform_fee_wallet,
+                ..
+            } => {
+                if let Some(game) = self.game.as_mut() {
+                    game.platform_fee_wallet = platform_fee_wallet;
+                    game.platform_fee_percent = Some(platform_fee_percent);
+                    game.min_stake = Some(min_stake);
+                    game.max_stake = Some(max_stake);
+                }
+            }
+            // Czynsz konta nie jest częścią wyniku gracza
+            SolanaIoEvent::PlayerStateClosed { .. } => {}
+            // Projekcja nie śledzi liczników pokoi
+            SolanaIoEvent::RoomReconciled { .. } => {}
+        }
+    }
+}
+
+/// Pobieranie historii programu z RPC walidatora
+#[cfg(feature = "rpc")]
+pub mod rpc {
+    use solana_client::{
+        client_error::ClientError,
+        rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+        rpc_config::RpcTransactionConfig,
+    };
+    use solana_program::pubkey::Pubkey;
+    use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+    use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+    use super::IndexedTransaction;
+
+    // Limit stron getSignaturesForAddress po stronie RPC
+    const PAGE_SIZE: usize = 1000;
+
+    /// Udane transakcje programu, najstarsze pierwsze. `until` - ostatnia już
+    /// zindeksowana sygnatura (pobieramy tylko nowsze), `limit` - maksymalna liczba.
+    pub fn fetch_transactions(
+        client: &RpcClient,
+        program_id: &Pubkey,
+        until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<IndexedTransaction>, Box<ClientError>> {
+        let commitment = CommitmentConfig::confirmed();
+        let mut statuses = Vec::new();
+        let mut before = None;
+        while statuses.len() < limit {
+            let page = client.get_signatures_for_address_with_config(
+                program_id,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(PAGE_SIZE.min(limit - statuses.len())),
+                    commitment: Some(commitment),
+                },
+            )?;
+            let last_page = page.len() < PAGE_SIZE.min(limit - statuses.len());
+            before = page.last().and_then(|status| status.signature.parse().ok());
+            statuses.extend(page);
+            if last_page || before.is_none() {
+                break;
+            }
+        }
+
+        let mut transactions = Vec::new();
+        // RPC zwraca najnowsze pierwsze
+        for status in statuses.into_iter().rev() {
+            if status.err.is_some() {
+                continue;
+            }
+            let Ok(signature) = status.signature.parse::<Signature>() else {
+                continue;
+            };
+            let transaction = client.get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(commitment),
+                    max_supported_transaction_version: Some(0),
+                },
+            )?;
+            let logs = match transaction.transaction.meta.map(|meta| meta.log_messages) {
+                Some(OptionSerializer::Some(logs)) => logs,
+                _ => continue,
+            };
+            transactions.push(IndexedTransaction {
+                signature: status.signature,
+                slot: transaction.slot,
+                block_time: transaction.block_time,
+                logs,
+            });
+        }
+        Ok(transactions)
+    }
+}
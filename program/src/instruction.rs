@@ -0,0 +1,667 @@
+// program/src/instruction.rs
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+
+use crate::error::SolanaIoError;
+use crate::pda::{
+    find_fee_vault_address, find_global_game_address, find_multisig_address,
+    find_player_state_address, find_reconciliation_address, find_room_address,
+};
+pub use crate::solana_io::{AuthorityRole, EatEvent, SolanaIoInstruction};
+pub use crate::ticket::{CashOutTicket, PartialCashOutTicket};
+
+// Buildery instrukcji - kolejność kont i flagi signer/writable muszą
+// zgadzać się z tym, co czytają procesory w `solana_io`.
+//
+// Authority (serwer/admin) jest zawsze kontem 0. Gdy rolą steruje multisig,
+// builder dostaje adres PDA multisig, a `with_multisig_signers` dopina członków.
+
+fn build(
+    program_id: &Pubkey,
+    instruction: &SolanaIoInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    // Serializacja borsh do Vec nie może się nie udać
+    let data = instruction.try_to_vec().expect("instruction serialization");
+    Instruction::new_with_bytes(*program_id, &data, accounts)
+}
+
+fn global_game_address(program_id: &Pubkey) -> Pubkey {
+    find_global_game_address(program_id).0
+}
+
+fn fee_vault_address(program_id: &Pubkey) -> Pubkey {
+    find_fee_vault_address(program_id).0
+}
+
+fn player_state_address(program_id: &Pubkey, player: &Pubkey) -> Pubkey {
+    find_player_state_address(program_id, player).0
+}
+
+/// Adres PDA pokoju
+pub fn room_address(program_id: &Pubkey, room_id: u32) -> Pubkey {
+    find_room_address(program_id, room_id).0
+}
+
+/// InitializeGame: [initializer (signer, writable), game PDA, fee vault PDA, system program, rent]
+pub fn initialize_game(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    server_authority: &Pubkey,
+    admin_authority: &Pubkey,
+    platform_fee_wallet: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::InitializeGame {
+            server_authority: *server_authority,
+            admin_authority: *admin_authority,
+            platform_fee_wallet: *platform_fee_wallet,
+        },
+        vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(global_game_address(program_id), false),
+            AccountMeta::new(fee_vault_address(program_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+/// JoinGame: [player (signer, writable), player state PDA, game PDA, room PDA, system program, rent]
+pub fn join_game(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    room_id: u32,
+    stake_amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::JoinGame {
+            room_id,
+            stake_amount,
+        },
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+/// UpdatePlayerValue: [server authority (signer), player state PDA, eaten player state PDA, game PDA, room PDA]
+pub fn update_player_value(
+    program_id: &Pubkey,
+    server_authority: &Pubkey,
+    room_id: u32,
+    player: &Pubkey,
+    eaten_player: &Pubkey,
+    eaten_value: u64,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::UpdatePlayerValue {
+            player: *player,
+            eaten_player: *eaten_player,
+            eaten_value,
+        },
+        vec![
+            AccountMeta::new_readonly(*server_authority, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new(player_state_address(program_id, eaten_player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+        ],
+    )
+}
+
+/// CashOut: [player (signer, writable), player state PDA, game PDA, room PDA, instructions sysvar]
+/// Bezpośrednio przed nią musi stać `ticket::ed25519_instruction` z podpisem biletu.
+/// Gdy server authority jest multisig, na końcu dopina się konto multisig (readonly).
+/// `close` zamyka stan gracza po wypłacie i zwraca mu czynsz.
+pub fn cash_out(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    room_id: u32,
+    ticket: &CashOutTicket,
+    close: bool,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::CashOut {
+            ticket: ticket.clone(),
+            close,
+        },
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+/// PartialCashOut: konta jak przy CashOut. Bezpośrednio przed nią musi stać
+/// `ticket::ed25519_instruction` z podpisem biletu.
+pub fn partial_cash_out(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    room_id: u32,
+    ticket: &PartialCashOutTicket,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::PartialCashOut {
+            ticket: ticket.clone(),
+        },
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+/// TopUp: [player (signer, writable), player state PDA, game PDA, room PDA, system program]
+pub fn top_up(program_id: &Pubkey, player: &Pubkey, room_id: u32, amount: u64) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::TopUp { amount },
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// UpdateGameParams: [admin authority (signer), game PDA]
+pub fn update_game_params(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    min_stake: Option<u64>,
+    max_stake: Option<u64>,
+    platform_fee_percent: Option<u8>,
+    new_platform_fee_wallet: Option<Pubkey>,
+    heartbeat_timeout: Option<i64>,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::UpdateGameParams {
+            min_stake,
+            max_stake,
+            platform_fee_percent,
+            new_platform_fee_wallet,
+            heartbeat_timeout,
+        },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new(global_game_address(program_id), false),
+        ],
+    )
+}
+
+/// ForceCleanup: [server authority (signer), player state PDA, game PDA, room PDA, player]
+pub fn force_cleanup(
+    program_id: &Pubkey,
+    server_authority: &Pubkey,
+    room_id: u32,
+    player: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::ForceCleanup { player: *player },
+        vec![
+            AccountMeta::new_readonly(*server_authority, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+            AccountMeta::new_readonly(*player, false),
+        ],
+    )
+}
+
+/// ForceRefund: [server authority (signer), player (writable), player state PDA, game PDA,
+/// room PDA]
+pub fn force_refund(
+    program_id: &Pubkey,
+    server_authority: &Pubkey,
+    room_id: u32,
+    player: &Pubkey,
+    charge_fee: bool,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::ForceRefund {
+            player: *player,
+            charge_fee,
+        },
+        vec![
+            AccountMeta::new_readonly(*server_authority, true),
+            AccountMeta::new(*player, false),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+        ],
+    )
+}
+
+/// WithdrawFees: [admin authority (signer), game PDA, fee vault PDA, recipient (writable)]
+pub fn withdraw_fees(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::WithdrawFees { amount },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new(global_game_address(program_id), false),
+            AccountMeta::new(fee_vault_address(program_id), false),
+            AccountMeta::new(*recipient, false),
+        ],
+    )
+}
+
+/// ProposeAuthority: [admin authority (signer), game PDA]
+pub fn propose_authority(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    role: AuthorityRole,
+    new_key: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::ProposeAuthority {
+            role,
+            new_key: *new_key,
+        },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new(global_game_address(program_id), false),
+        ],
+    )
+}
+
+/// AcceptAuthority: [zaproponowany klucz (signer), game PDA]
+pub fn accept_authority(
+    program_id: &Pubkey,
+    new_authority: &Pubkey,
+    role: AuthorityRole,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::AcceptAuthority { role },
+        vec![
+            AccountMeta::new_readonly(*new_authority, true),
+            AccountMeta::new(global_game_address(program_id), false),
+        ],
+    )
+}
+
+/// CancelProposal: [admin authority (signer), game PDA]
+pub fn cancel_proposal(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    role: AuthorityRole,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::CancelProposal { role },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new(global_game_address(program_id), false),
+        ],
+    )
+}
+
+/// Przerabia instrukcję z authority na konto multisig: konto 0 przestaje być
+/// signerem, a podpisujący członkowie trafiają na koniec listy kont
+pub fn with_multisig_signers(mut instruction: Instruction, signers: &[Pubkey]) -> Instruction {
+    instruction.accounts[0].is_signer = false;
+    instruction
+        .accounts
+        .extend(signers.iter().map(|signer| AccountMeta::new_readonly(*signer, true)));
+    instruction
+}
+
+/// Adres PDA multisig dla roli
+pub fn multisig_address(program_id: &Pubkey, role: AuthorityRole) -> Pubkey {
+    find_multisig_address(program_id, role).0
+}
+
+/// CreateMultisig: [admin authority (signer), payer (signer, writable), game PDA, multisig PDA, system program, rent]
+pub fn create_multisig(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    payer: &Pubkey,
+    role: AuthorityRole,
+    signers: &[Pubkey],
+    threshold: u8,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::CreateMultisig {
+            role,
+            signers: signers.to_vec(),
+            threshold,
+        },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(multisig_address(program_id, role), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+/// SetMultisigSigners: [admin authority (signer), game PDA, multisig PDA (writable)]
+pub fn set_multisig_signers(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    role: AuthorityRole,
+    signers: &[Pubkey],
+    threshold: u8,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::SetMultisigSigners {
+            role,
+            signers: signers.to_vec(),
+            threshold,
+        },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(multisig_address(program_id, role), false),
+        ],
+    )
+}
+
+/// SetPaused: [admin authority (signer), game PDA]
+pub fn set_paused(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    joins: bool,
+    gameplay: bool,
+    cashouts: bool,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::SetPaused {
+            joins,
+            gameplay,
+            cashouts,
+        },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new(global_game_address(program_id), false),
+        ],
+    )
+}
+
+/// Heartbeat: [server authority (signer), game PDA]
+pub fn heartbeat(program_id: &Pubkey, server_authority: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::Heartbeat,
+        vec![
+            AccountMeta::new_readonly(*server_authority, true),
+            AccountMeta::new(global_game_address(program_id), false),
+        ],
+    )
+}
+
+/// EmergencyWithdraw: [player (signer, writable), player state PDA, game PDA, room PDA]
+pub fn emergency_withdraw(program_id: &Pubkey, player: &Pubkey, room_id: u32) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::EmergencyWithdraw,
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+        ],
+    )
+}
+
+/// CreateRoom: [admin authority (signer), payer (signer, writable), game PDA, room PDA, system program, rent]
+pub fn create_room(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    payer: &Pubkey,
+    room_id: u32,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::CreateRoom { room_id },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+/// UpdateRoomParams: [admin authority (signer), game PDA, room PDA (writable)]
+pub fn update_room_params(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    room_id: u32,
+    min_stake: Option<u64>,
+    max_stake: Option<u64>,
+) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::UpdateRoomParams {
+            room_id,
+            min_stake,
+            max_stake,
+        },
+        vec![
+            AccountMeta::new_readonly(*admin_authority, true),
+            AccountMeta::new_readonly(global_game_address(program_id), false),
+            AccountMeta::new(room_address(program_id, room_id), false),
+        ],
+    )
+}
+
+/// CollectRoomFees: [room PDA, game PDA, fee vault PDA] - bez podpisu
+pub fn collect_room_fees(program_id: &Pubkey, room_id: u32) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::CollectRoomFees,
+        vec![
+            AccountMeta::new(room_address(program_id, room_id), false),
+            AccountMeta::new(global_game_address(program_id), false),
+            AccountMeta::new(fee_vault_address(program_id), false),
+        ],
+    )
+}
+
+/// BatchUpdatePlayerValues: [server authority (signer), game PDA, room PDA, player state PDA...]
+/// Konta stanu graczy w kolejności `EatEvent::players(events)`.
+pub fn batch_update_player_values(
+    program_id: &Pubkey,
+    server_authority: &Pubkey,
+    room_id: u32,
+    events: &[EatEvent],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*server_authority, true),
+        AccountMeta::new_readonly(global_game_address(program_id), false),
+        AccountMeta::new(room_address(program_id, room_id), false),
+    ];
+    accounts.extend(
+        EatEvent::players(events)
+            .iter()
+            .map(|player| AccountMeta::new(player_state_address(program_id, player), false)),
+    );
+    build(
+        program_id,
+        &SolanaIoInstruction::BatchUpdatePlayerValues {
+            events: events.to_vec(),
+        },
+        accounts,
+    )
+}
+
+/// ClosePlayerState: [player (signer, writable), player state PDA]
+pub fn close_player_state(program_id: &Pubkey, player: &Pubkey) -> Instruction {
+    build(
+        program_id,
+        &SolanaIoInstruction::ClosePlayerState,
+        vec![
+            AccountMeta::new(*player, true),
+            AccountMeta::new(player_state_address(program_id, player), false),
+        ],
+    )
+}
+
+/// CheckSolvency: [game PDA, fee vault PDA, ...room PDA] - wszystkie readonly
+pub fn check_solvency(program_id: &Pubkey, room_ids: &[u32]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(global_game_address(program_id), false),
+        AccountMeta::new_readonly(fee_vault_address(program_id), false),
+    ];
+    accounts.extend(
+        room_ids
+            .iter()
+            .map(|room_id| AccountMeta::new_readonly(room_address(program_id, *room_id), false)),
+    );
+    build(program_id, &SolanaIoInstruction::CheckSolvency, accounts)
+}
+
+/// Adres PDA uzgodnienia pokoju
+pub fn reconciliation_address(program_id: &Pubkey, room_id: u32) -> Pubkey {
+    find_reconciliation_address(program_id, room_id).0
+}
+
+/// Najwięcej stanów graczy w jednej instrukcji Reconcile - transakcja zostawia miejsce
+/// na członków multisig. Tego samego limitu używa `solana-io-cli reconcile`.
+pub const MAX_RECONCILE_PAGE_SIZE: usize = 20;
+
+/// Reconcile: [admin authority (signer), payer (signer, writable), game PDA, room PDA (writable),
+/// reconciliation PDA (writable), system program, rent, ...player state PDA]
+///
+/// `players` muszą być posortowane rosnąco i leżeć za graczami poprzednich stron.
+/// Więcej niż `MAX_RECONCILE_PAGE_SIZE` graczy to błąd - większe pokoje dzieli `reconcile_pages`.
+pub fn reconcile(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    payer: &Pubkey,
+    room_id: u32,
+    players: &[Pubkey],
+    start: bool,
+    finish: bool,
+) -> Result<Instruction, SolanaIoError> {
+    if players.len() > MAX_RECONCILE_PAGE_SIZE {
+        return Err(SolanaIoError::ReconcilePageTooLarge);
+    }
+    Ok(reconcile_page(
+        program_id,
+        admin_authority,
+        payer,
+        room_id,
+        players,
+        start,
+        finish,
+    ))
+}
+
+/// Strona Reconcile o długości już sprawdzonej przez wywołującego
+fn reconcile_page(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    payer: &Pubkey,
+    room_id: u32,
+    players: &[Pubkey],
+    start: bool,
+    finish: bool,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*admin_authority, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(global_game_address(program_id), false),
+        AccountMeta::new(room_address(program_id, room_id), false),
+        AccountMeta::new(reconciliation_address(program_id, room_id), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    accounts.extend(
+        players.iter().map(|player| {
+            AccountMeta::new_readonly(player_state_address(program_id, player), false)
+        }),
+    );
+    build(
+        program_id,
+        &SolanaIoInstruction::Reconcile {
+            room_id,
+            player_count: players.len() as u8,
+            start,
+            finish,
+        },
+        accounts,
+    )
+}
+
+/// Pełne uzgodnienie pokoju: gracze posortowani i podzieleni na strony po `page_size`,
+/// przyciętym do 1..=`MAX_RECONCILE_PAGE_SIZE`.
+/// Każdą instrukcję wysyła się w osobnej transakcji, po kolei - pierwsza ma `start`,
+/// ostatnia `finish`. Pusty pokój to jedna instrukcja z oboma flagami.
+pub fn reconcile_pages(
+    program_id: &Pubkey,
+    admin_authority: &Pubkey,
+    payer: &Pubkey,
+    room_id: u32,
+    players: &[Pubkey],
+    page_size: usize,
+) -> Vec<Instruction> {
+    let mut players = players.to_vec();
+    players.sort();
+    players.dedup();
+    let pages: Vec<&[Pubkey]> = if players.is_empty() {
+        vec![&[]]
+    } else {
+        players
+            .chunks(page_size.clamp(1, MAX_RECONCILE_PAGE_SIZE))
+            .collect()
+    };
+    let last = pages.len() - 1;
+    pages
+        .iter()
+        .enumerate()
+        .map(|(i, page)| {
+            reconcile_page(
+                program_id,
+                admin_authority,
+                payer,
+                room_id,
+                page,
+                i == 0,
+                i == last,
+            )
+        })
+        .collect()
+}
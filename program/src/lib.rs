@@ -1,5 +1,15 @@
 // program/src/lib.rs
+pub mod accounts;
+#[cfg(not(target_os = "solana"))]
+pub mod cli;
+pub mod error;
+pub mod events;
+#[cfg(not(target_os = "solana"))]
+pub mod indexer;
+pub mod instruction;
+pub mod pda;
 pub mod solana_io;
+pub mod ticket;
 
 use solana_program::entrypoint;
 
@@ -9,13 +19,21 @@ entrypoint!(process_instruction);
 use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
+    program_error::PrintProgramError,
     pubkey::Pubkey,
 };
 
+use crate::error::SolanaIoError;
+
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    solana_io::process_instruction(program_id, accounts, instruction_data)
+    if let Err(error) = solana_io::process_instruction(program_id, accounts, instruction_data) {
+        // Wypisz czytelny komunikat dla naszych kodów błędów
+        error.print::<SolanaIoError>();
+        return Err(error);
+    }
+    Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+// program/src/pda.rs
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::SolanaIoError;
+use crate::solana_io::AuthorityRole;
+
+/// Seed PDA globalnej gry
+pub const GLOBAL_GAME_SEED: &[u8] = b"global_game";
+/// Seed PDA stanu gracza (+ pubkey gracza)
+pub const PLAYER_STATE_SEED: &[u8] = b"player_state";
+/// Seed PDA skarbca prowizji
+pub const FEE_VAULT_SEED: &[u8] = b"fee_vault";
+/// Seed PDA multisig (+ seed roli)
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+/// Seed PDA pokoju (+ id pokoju little-endian)
+pub const ROOM_SEED: &[u8] = b"room";
+/// Seed PDA uzgodnienia liczników pokoju (+ id pokoju little-endian)
+pub const RECONCILIATION_SEED: &[u8] = b"reconciliation";
+
+/// Adres i bump PDA globalnej gry (drogie - tylko off-chain i przy tworzeniu konta)
+pub fn find_global_game_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_GAME_SEED], program_id)
+}
+
+/// Adres i bump PDA stanu gracza (drogie - tylko off-chain i przy tworzeniu konta)
+pub fn find_player_state_address(program_id: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PLAYER_STATE_SEED, player.as_ref()], program_id)
+}
+
+/// Adres i bump PDA skarbca prowizji (drogie - tylko off-chain i przy tworzeniu konta)
+pub fn find_fee_vault_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_VAULT_SEED], program_id)
+}
+
+/// Adres i bump PDA multisig dla roli (drogie - tylko off-chain i przy tworzeniu konta)
+pub fn find_multisig_address(program_id: &Pubkey, role: AuthorityRole) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MULTISIG_SEED, role.seed()], program_id)
+}
+
+/// Adres i bump PDA pokoju (drogie - tylko off-chain i przy tworzeniu konta)
+pub fn find_room_address(program_id: &Pubkey, room_id: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ROOM_SEED, &room_id.to_le_bytes()], program_id)
+}
+
+/// Adres i bump PDA uzgodnienia pokoju (drogie - tylko off-chain i przy tworzeniu konta)
+pub fn find_reconciliation_address(program_id: &Pubkey, room_id: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECONCILIATION_SEED, &room_id.to_le_bytes()], program_id)
+}
+
+/// Odtwarza PDA globalnej gry z zapisanego bumpa
+pub fn create_global_game_address(program_id: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(&[GLOBAL_GAME_SEED, &[bump]], program_id)
+        .map_err(|_| SolanaIoError::InvalidGameAccount.into())
+}
+
+/// Odtwarza PDA stanu gracza z zapisanego bumpa
+pub fn create_player_state_address(
+    program_id: &Pubkey,
+    player: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(&[PLAYER_STATE_SEED, player.as_ref(), &[bump]], program_id)
+        .map_err(|_| SolanaIoError::InvalidPlayerStateAccount.into())
+}
+
+/// Odtwarza PDA skarbca prowizji z zapisanego bumpa
+pub fn create_fee_vault_address(program_id: &Pubkey, bump: u8) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(&[FEE_VAULT_SEED, &[bump]], program_id)
+        .map_err(|_| SolanaIoError::InvalidFeeVaultAccount.into())
+}
+
+/// Odtwarza PDA multisig dla roli z zapisanego bumpa
+pub fn create_multisig_address(
+    program_id: &Pubkey,
+    role: AuthorityRole,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(&[MULTISIG_SEED, role.seed(), &[bump]], program_id)
+        .map_err(|_| SolanaIoError::InvalidMultisigAccount.into())
+}
+
+/// Odtwarza PDA pokoju z zapisanego bumpa
+pub fn create_room_address(
+    program_id: &Pubkey,
+    room_id: u32,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(&[ROOM_SEED, &room_id.to_le_bytes(), &[bump]], program_id)
+        .map_err(|_| SolanaIoError::InvalidRoomAccount.into())
+}
+
+/// Odtwarza PDA uzgodnienia pokoju z zapisanego bumpa
+pub fn create_reconciliation_address(
+    program_id: &Pubkey,
+    room_id: u32,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    Pubkey::create_program_address(
+        &[RECONCILIATION_SEED, &room_id.to_le_bytes(), &[bump]],
+        program_id,
+    )
+    .map_err(|_| SolanaIoError::InvalidReconciliationAccount.into())
+}
@@ -1,6 +1,6 @@
 // program/src/solana_io.rs
 use solana_program::{
-    account_info::{next_account_info, AccountInfo},
+    account_info::AccountInfo,
     entrypoint::ProgramResult,
     msg,
     program_error::ProgramError,
@@ -10,6 +10,21 @@ use solana_program::{
     sysvar::{rent::Rent, Sysvar, clock::Clock},
 };
 use borsh::{BorshDeserialize, BorshSerialize};
+use crate::accounts::{
+    CashOutAccounts, CheckSolvencyAccounts, ForceRefundAccounts, ReconcileAccounts, TopUpAccounts, ClosePlayerStateAccounts, ForceCleanupAccounts, InitializeGameAccounts, JoinGameAccounts,
+    check_authority_signature, AuthorityTransferAccounts, BatchUpdatePlayerValuesAccounts,
+    CreateMultisigAccounts, CreateRoomAccounts, EmergencyWithdrawAccounts, HeartbeatAccounts,
+    SetMultisigSignersAccounts, SetPausedAccounts, UpdateGameParamsAccounts, UpdatePlayerValueAccounts,
+    UpdateRoomParamsAccounts, WithdrawFeesAccounts, CollectRoomFeesAccounts,
+};
+use crate::error::SolanaIoError;
+use crate::events::SolanaIoEvent;
+use crate::pda::{
+    FEE_VAULT_SEED, GLOBAL_GAME_SEED, MULTISIG_SEED, PLAYER_STATE_SEED, RECONCILIATION_SEED, ROOM_SEED,
+};
+use crate::ticket::{
+    check_ticket_signers, ed25519_signatures, CashOutTicket, PartialCashOutTicket,
+};
 
 /// Struktura gracza w grze
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -21,44 +36,242 @@ pub struct PlayerState {
     pub joined_at: i64,              // 8 bajtów - timestamp dołączenia
     pub last_cashout: i64,           // 8 bajtów - ostatnia wypłata
     pub total_earned: u64,           // 8 bajtów - łączne zarobki
+    pub bump: u8,                    // 1 bajt - bump PDA stanu gracza
+    pub cashout_nonce: u64,          // 8 bajtów - nonce następnego biletu wypłaty
+    pub room_id: u32,                // 4 bajty - pokój, w którym gracz gra (lub grał ostatnio)
+    pub session_stake: u64,          // 8 bajtów - stawka bieżącej sesji (dołączenie + TopUp)
 }
 
 impl PlayerState {
-    pub const SIZE: usize = 32 + 8 + 8 + 1 + 8 + 8 + 8; // 73 bajty
+    pub const SIZE: usize = 32 + 8 + 8 + 1 + 8 + 8 + 8 + 1 + 8 + 4 + 8; // 94 bajty
+    /// Przesunięcie `room_id` w danych konta (filtry memcmp w RPC)
+    pub const ROOM_ID_OFFSET: usize = Self::SIZE - 8 - 4;
+}
+
+/// Pokój gry - własna pula i licznik graczy, żeby zjedzenia w różnych
+/// pokojach nie blokowały jednego konta
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Room {
+    pub is_initialized: bool, // 1 bajt
+    pub room_id: u32,         // 4 bajty - id pokoju (seed PDA)
+    pub total_pool: u64,      // 8 bajtów - pula pokoju (lamporty leżą na koncie pokoju)
+    pub active_players: u32,  // 4 bajty - liczba aktywnych graczy
+    pub total_players: u32,   // 4 bajty - wszyscy gracze, którzy dołączyli do pokoju
+    pub min_stake: u64,       // 8 bajtów - minimalna stawka
+    pub max_stake: u64,       // 8 bajtów - maksymalna stawka
+    pub created_at: i64,      // 8 bajtów
+    pub bump: u8,             // 1 bajt - bump PDA pokoju
+    pub fee_collected: u64,   // 8 bajtów - prowizje z wypłat, leżą na koncie pokoju do CollectRoomFees
+}
+
+impl Room {
+    pub const SIZE: usize = 1 + 4 + 8 + 4 + 4 + 8 + 8 + 8 + 1 + 8; // 54 bajty
+    
+    /// Saldo, które konto pokoju musi trzymać: czynsz + pula graczy + niezebrane prowizje
+    pub fn required_lamports(&self, rent: &Rent) -> Option<u64> {
+        rent.minimum_balance(Self::SIZE)
+            .checked_add(self.total_pool)?
+            .checked_add(self.fee_collected)
+    }
+}
+
+/// Zapis uzgodnienia liczników pokoju (Reconcile) - sumy z kolejnych stron
+/// stanów graczy i wynik ostatniego zakończonego uzgodnienia
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct Reconciliation {
+    pub is_initialized: bool,         // 1 bajt
+    pub room_id: u32,                 // 4 bajty - uzgadniany pokój
+    pub bump: u8,                     // 1 bajt - bump PDA uzgodnienia
+    pub in_progress: bool,            // 1 bajt - rozpoczęte, jeszcze nie zakończone
+    pub cursor: Pubkey,               // 32 bajty - ostatni policzony gracz (strony idą rosnąco)
+    pub players_scanned: u32,         // 4 bajty - policzone stany graczy
+    pub active_players: u32,          // 4 bajty - aktywni gracze wśród policzonych
+    pub total_pool: u64,              // 8 bajtów - suma current_value aktywnych graczy
+    pub started_at: i64,              // 8 bajtów
+    pub completed_at: i64,            // 8 bajtów - 0, dopóki żadne uzgodnienie się nie zakończyło
+    pub previous_active_players: u32, // 4 bajty - licznik pokoju przed ostatnim uzgodnieniem
+    pub previous_total_pool: u64,     // 8 bajtów - pula pokoju przed ostatnim uzgodnieniem
+}
+
+impl Reconciliation {
+    pub const SIZE: usize = 1 + 4 + 1 + 1 + 32 + 4 + 4 + 8 + 8 + 8 + 4 + 8; // 83 bajty
 }
 
-/// Globalna gra - pojedyncza instancja
+/// Globalna konfiguracja - wspólne parametry, authority i flagi wszystkich pokoi
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct GlobalGame {
     pub is_initialized: bool,        // 1 bajt
-    pub total_pool: u64,            // 8 bajtów - całkowita pula
-    pub platform_fee_collected: u64, // 8 bajtów - zebrane prowizje
-    pub active_players: u32,         // 4 bajty - liczba aktywnych graczy
-    pub total_players: u32,          // 4 bajty - wszyscy gracze
+    pub platform_fee_collected: u64, // 8 bajtów - prowizje zebrane z pokoi do skarbca (do wypłaty)
     pub created_at: i64,            // 8 bajtów
-    pub min_stake: u64,             // 8 bajtów - minimalna stawka
-    pub max_stake: u64,             // 8 bajtów - maksymalna stawka
+    pub min_stake: u64,             // 8 bajtów - domyślna minimalna stawka nowych pokoi
+    pub max_stake: u64,             // 8 bajtów - domyślna maksymalna stawka nowych pokoi
     pub platform_fee_percent: u8,    // 1 bajt - procent prowizji
     pub server_authority: Pubkey,    // 32 bajty - adres serwera z uprawnieniami
+    pub bump: u8,                    // 1 bajt - bump PDA globalnej gry
+    pub platform_fee_wallet: Pubkey, // 32 bajty - portfel na prowizje
+    pub fee_vault_bump: u8,          // 1 bajt - bump PDA skarbca prowizji
+    pub total_fees_withdrawn: u64,   // 8 bajtów - prowizje wypłacone ze skarbca
+    pub admin_authority: Pubkey,     // 32 bajty - klucz administracyjny (parametry, prowizje)
+    pub pending_server_authority: Option<Pubkey>, // 33 bajty - zaproponowany nowy klucz serwera
+    pub pending_admin_authority: Option<Pubkey>,  // 33 bajty - zaproponowany nowy klucz admina
+    pub paused_joins: bool,          // 1 bajt - wstrzymane JoinGame
+    pub paused_gameplay: bool,       // 1 bajt - wstrzymane UpdatePlayerValue i ForceCleanup
+    pub paused_cashouts: bool,       // 1 bajt - wstrzymane CashOut
+    pub last_heartbeat: i64,         // 8 bajtów - ostatni Heartbeat serwera
+    pub heartbeat_timeout: i64,      // 8 bajtów - po ilu sekundach ciszy serwer jest martwy
+}
+
+/// Rola z uprawnieniami, przekazywana dwuetapowo (ProposeAuthority -> AcceptAuthority)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityRole {
+    /// Gorący klucz serwera gry (UpdatePlayerValue, ForceCleanup)
+    Server,
+    /// Klucz administracyjny (parametry, prowizje, przekazywanie ról)
+    Admin,
+}
+
+impl AuthorityRole {
+    /// Seed roli w PDA multisig
+    pub fn seed(&self) -> &'static [u8] {
+        match self {
+            AuthorityRole::Server => b"server",
+            AuthorityRole::Admin => b"admin",
+        }
+    }
+}
+
+/// Multisig M-z-N dla roli. Adres PDA multisig ustawiony jako authority roli
+/// zamiast pojedynczego klucza wymaga `threshold` podpisów członków.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Multisig {
+    pub is_initialized: bool,                     // 1 bajt
+    pub role: AuthorityRole,                      // 1 bajt - rola, dla której jest PDA
+    pub threshold: u8,                            // 1 bajt - wymagana liczba podpisów
+    pub signer_count: u8,                         // 1 bajt - liczba członków
+    pub signers: [Pubkey; Multisig::MAX_SIGNERS], // 32 * 11 bajtów - członkowie
+    pub bump: u8,                                 // 1 bajt - bump PDA multisig
+}
+
+impl Multisig {
+    pub const MAX_SIGNERS: usize = 11;
+    pub const SIZE: usize = 1 + 1 + 1 + 1 + 32 * Self::MAX_SIGNERS + 1;
+    
+    /// Tworzy multisig po walidacji listy członków i progu
+    pub fn new(
+        role: AuthorityRole,
+        signers: &[Pubkey],
+        threshold: u8,
+        bump: u8,
+    ) -> Result<Self, ProgramError> {
+        let mut multisig = Self {
+            is_initialized: true,
+            role,
+            threshold: 0,
+            signer_count: 0,
+            signers: [Pubkey::default(); Self::MAX_SIGNERS],
+            bump,
+        };
+        multisig.set_signers(signers, threshold)?;
+        Ok(multisig)
+    }
+    
+    /// Podmienia członków i próg
+    pub fn set_signers(&mut self, signers: &[Pubkey], threshold: u8) -> Result<(), ProgramError> {
+        if signers.is_empty()
+            || signers.len() > Self::MAX_SIGNERS
+            || threshold == 0
+            || threshold as usize > signers.len()
+        {
+            return Err(SolanaIoError::InvalidMultisigConfig.into());
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if signers[i + 1..].contains(signer) {
+                return Err(SolanaIoError::InvalidMultisigConfig.into());
+            }
+        }
+        
+        self.signers = [Pubkey::default(); Self::MAX_SIGNERS];
+        self.signers[..signers.len()].copy_from_slice(signers);
+        self.signer_count = signers.len() as u8;
+        self.threshold = threshold;
+        Ok(())
+    }
+    
+    /// Aktualni członkowie
+    pub fn members(&self) -> &[Pubkey] {
+        &self.signers[..self.signer_count as usize]
+    }
 }
 
 impl GlobalGame {
     pub const SIZE: usize = 256; // Rozmiar z dodatkowym polem
     pub const HEADER_SIZE: usize = 4;
     pub const MAX_PLAYERS: usize = 1000; // Maksymalna liczba graczy
-    
-    pub fn new(created_at: i64, server_authority: Pubkey) -> Self {
+    pub const DEFAULT_HEARTBEAT_TIMEOUT: i64 = 300; // 5 minut
+    
+    pub fn new(
+        created_at: i64,
+        server_authority: Pubkey,
+        admin_authority: Pubkey,
+        platform_fee_wallet: Pubkey,
+        bump: u8,
+        fee_vault_bump: u8,
+    ) -> Self {
         Self {
             is_initialized: true,
-            total_pool: 0,
             platform_fee_collected: 0,
-            active_players: 0,
-            total_players: 0,
             created_at,
             min_stake: 50_000_000,     // ZMIANA: 0.05 SOL minimum
             max_stake: 10_000_000_000, // 10 SOL maximum
             platform_fee_percent: 5,   // 5% prowizji
             server_authority,          // Zapisz adres serwera
+            bump,
+            platform_fee_wallet,
+            fee_vault_bump,
+            total_fees_withdrawn: 0,
+            admin_authority,
+            pending_server_authority: None,
+            pending_admin_authority: None,
+            paused_joins: false,
+            paused_gameplay: false,
+            paused_cashouts: false,
+            last_heartbeat: created_at,
+            heartbeat_timeout: Self::DEFAULT_HEARTBEAT_TIMEOUT,
+        }
+    }
+    
+    /// Serwer milczy dłużej niż `heartbeat_timeout`
+    pub fn is_server_stale(&self, now: i64) -> bool {
+        now.saturating_sub(self.last_heartbeat) > self.heartbeat_timeout
+    }
+    
+    /// Aktualny klucz dla roli
+    pub fn authority(&self, role: AuthorityRole) -> Pubkey {
+        match role {
+            AuthorityRole::Server => self.server_authority,
+            AuthorityRole::Admin => self.admin_authority,
+        }
+    }
+    
+    /// Oczekująca propozycja dla roli
+    pub fn pending_authority(&self, role: AuthorityRole) -> Option<Pubkey> {
+        match role {
+            AuthorityRole::Server => self.pending_server_authority,
+            AuthorityRole::Admin => self.pending_admin_authority,
+        }
+    }
+    
+    fn pending_authority_mut(&mut self, role: AuthorityRole) -> &mut Option<Pubkey> {
+        match role {
+            AuthorityRole::Server => &mut self.pending_server_authority,
+            AuthorityRole::Admin => &mut self.pending_admin_authority,
+        }
+    }
+    
+    fn authority_mut(&mut self, role: AuthorityRole) -> &mut Pubkey {
+        match role {
+            AuthorityRole::Server => &mut self.server_authority,
+            AuthorityRole::Admin => &mut self.admin_authority,
         }
     }
     
@@ -104,16 +317,43 @@ impl GlobalGame {
     }
 }
 
+/// Pojedyncze zjedzenie w BatchUpdatePlayerValues
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EatEvent {
+    pub player: Pubkey,
+    pub eaten_player: Pubkey,
+    pub eaten_value: u64,
+}
+
+impl EatEvent {
+    /// Gracze z paczki w kolejności pierwszego wystąpienia - w tej kolejności
+    /// przekazuje się ich konta stanu (każde raz, także przy łańcuchach zjedzeń)
+    pub fn players(events: &[EatEvent]) -> Vec<Pubkey> {
+        let mut players: Vec<Pubkey> = Vec::new();
+        for event in events {
+            for key in [event.player, event.eaten_player] {
+                if !players.contains(&key) {
+                    players.push(key);
+                }
+            }
+        }
+        players
+    }
+}
+
 /// Instrukcje programu
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum SolanaIoInstruction {
     /// Inicjalizuje globalną grę (tylko raz)
     InitializeGame {
-        server_authority: Pubkey, // Adres serwera z uprawnieniami
+        server_authority: Pubkey,    // Gorący klucz serwera - tylko instrukcje rozgrywki
+        admin_authority: Pubkey,     // Klucz administracyjny - parametry i prowizje
+        platform_fee_wallet: Pubkey, // Portfel na prowizje
     },
     
-    /// Gracz dołącza do gry z określoną stawką
+    /// Gracz dołącza do pokoju z określoną stawką
     JoinGame {
+        room_id: u32,
         stake_amount: u64,
     },
     
@@ -124,21 +364,134 @@ pub enum SolanaIoInstruction {
         eaten_value: u64,
     },
     
-    /// Gracz wypłaca swoje środki i opuszcza grę
-    CashOut,
+    /// Gracz wypłaca swoje środki i opuszcza grę. Wymaga biletu podpisanego przez
+    /// server authority w instrukcji ed25519 tuż przed tą instrukcją.
+    CashOut {
+        ticket: CashOutTicket,
+        /// Zamknij stan gracza po wypłacie (jak ClosePlayerState)
+        close: bool,
+    },
     
-    /// Admin może zaktualizować parametry gry
+    /// Admin authority może zaktualizować parametry gry
     UpdateGameParams {
         min_stake: Option<u64>,
         max_stake: Option<u64>,
         platform_fee_percent: Option<u8>,
-        new_server_authority: Option<Pubkey>,
+        new_platform_fee_wallet: Option<Pubkey>,
+        heartbeat_timeout: Option<i64>,
     },
     
     /// Server authority może wymusić czyszczenie stanu gracza (bez wypłaty)
     ForceCleanup {
         player: Pubkey,
     },
+    
+    /// Admin authority wypłaca zebrane prowizje ze skarbca na wybrane konto
+    WithdrawFees {
+        amount: u64,
+    },
+    
+    /// Admin authority proponuje nowy klucz dla roli - zmiana wchodzi dopiero po AcceptAuthority
+    ProposeAuthority {
+        role: AuthorityRole,
+        new_key: Pubkey,
+    },
+    
+    /// Zaproponowany klucz podpisuje i przejmuje rolę
+    AcceptAuthority {
+        role: AuthorityRole,
+    },
+    
+    /// Admin authority wycofuje oczekującą propozycję
+    CancelProposal {
+        role: AuthorityRole,
+    },
+    
+    /// Admin authority tworzy multisig dla roli; włącza się go przez ProposeAuthority
+    /// z adresem PDA multisig i AcceptAuthority podpisane przez członków
+    CreateMultisig {
+        role: AuthorityRole,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+    
+    /// Admin authority podmienia członków i próg multisig roli
+    SetMultisigSigners {
+        role: AuthorityRole,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+    
+    /// Admin authority wstrzymuje/wznawia grupy instrukcji (awaria, desynchronizacja serwera)
+    SetPaused {
+        joins: bool,
+        gameplay: bool,
+        cashouts: bool,
+    },
+    
+    /// Server authority potwierdza, że serwer żyje
+    Heartbeat,
+    
+    /// Gracz wypłaca `current_value` bez prowizji, gdy serwer przestał wysyłać Heartbeat
+    EmergencyWithdraw,
+    
+    /// Admin authority tworzy pokój z domyślnymi limitami stawek z konfiguracji
+    CreateRoom {
+        room_id: u32,
+    },
+    
+    /// Admin authority zmienia limity stawek pokoju
+    UpdateRoomParams {
+        room_id: u32,
+        min_stake: Option<u64>,
+        max_stake: Option<u64>,
+    },
+    
+    /// Przenosi prowizje zebrane w pokoju do skarbca, skąd wypłaca je WithdrawFees
+    CollectRoomFees,
+    
+    /// Server authority stosuje po kolei wiele zjedzeń w jednym pokoju - jak
+    /// UpdatePlayerValue, ale jedna transakcja na paczkę zdarzeń
+    BatchUpdatePlayerValues {
+        events: Vec<EatEvent>,
+    },
+    
+    /// Nieaktywny gracz bez wartości zamyka swój stan i odzyskuje czynsz
+    ClosePlayerState,
+    
+    /// Tylko odczyt: sprawdza, że skarbiec i przekazane pokoje pokrywają czynsz
+    /// i zapisane salda - dla monitoringu przez symulację transakcji
+    CheckSolvency,
+    
+    /// Admin authority przelicza `active_players` i `total_pool` pokoju ze stanów graczy.
+    /// Stany idą stronami, posortowane rosnąco po adresie gracza: `start` zeruje zapis
+    /// uzgodnienia, `finish` nadpisuje liczniki pokoju zebranymi sumami.
+    /// Wymaga wstrzymanych dołączeń, rozgrywki i wypłat.
+    Reconcile {
+        room_id: u32,
+        player_count: u8, // ile kont po stałych to stany graczy (dalej: członkowie multisig)
+        start: bool,
+        finish: bool,
+    },
+    
+    /// Server authority zwraca aktywnemu graczowi `current_value` na portfel - dla graczy
+    /// zgubionych przez awarię serwera (ForceCleanup jest dla duchów, już zjedzonych)
+    ForceRefund {
+        player: Pubkey,
+        charge_fee: bool, // potrącić prowizję platformy jak przy CashOut
+    },
+    
+    /// Gracz wypłaca część wartości (z prowizją) i zostaje w grze. Jak CashOut wymaga
+    /// biletu serwera w instrukcji ed25519 tuż przed tą instrukcją.
+    PartialCashOut {
+        ticket: PartialCashOutTicket,
+    },
+    
+    /// Aktywny gracz dopłaca do stawki w trakcie sesji - łączna stawka sesji
+    /// nie może przekroczyć `max_stake` pokoju
+    TopUp {
+        amount: u64,
+    },
 }
 
 /// Przetwarzanie instrukcji programu
@@ -150,30 +503,128 @@ pub fn process_instruction(
     let instruction = SolanaIoInstruction::try_from_slice(input)?;
     
     match instruction {
-        SolanaIoInstruction::InitializeGame { server_authority } => {
+        SolanaIoInstruction::InitializeGame {
+            server_authority,
+            admin_authority,
+            platform_fee_wallet,
+        } => {
             msg!("Initializing Solana.io global game with server authority: {}", server_authority);
-            process_initialize_game(program_id, accounts, server_authority)
+            process_initialize_game(
+                program_id,
+                accounts,
+                server_authority,
+                admin_authority,
+                platform_fee_wallet,
+            )
         },
-        SolanaIoInstruction::JoinGame { stake_amount } => {
-            msg!("Player joining game with stake: {} lamports", stake_amount);
-            process_join_game(program_id, accounts, stake_amount)
+        SolanaIoInstruction::JoinGame { room_id, stake_amount } => {
+            msg!("Player joining room {} with stake: {} lamports", room_id, stake_amount);
+            process_join_game(program_id, accounts, room_id, stake_amount)
         },
         SolanaIoInstruction::UpdatePlayerValue { player, eaten_player, eaten_value } => {
             msg!("Updating player value after eating");
             process_update_player_value(program_id, accounts, player, eaten_player, eaten_value)
         },
-        SolanaIoInstruction::CashOut => {
-            msg!("Player cashing out");
-            process_cash_out(program_id, accounts)
+        SolanaIoInstruction::CashOut { ticket, close } => {
+            msg!("Player cashing out with ticket nonce: {}", ticket.nonce);
+            process_cash_out(program_id, accounts, ticket, close)
         },
-        SolanaIoInstruction::UpdateGameParams { min_stake, max_stake, platform_fee_percent, new_server_authority } => {
+        SolanaIoInstruction::UpdateGameParams {
+            min_stake,
+            max_stake,
+            platform_fee_percent,
+            new_platform_fee_wallet,
+            heartbeat_timeout,
+        } => {
             msg!("Updating game parameters");
-            process_update_game_params(program_id, accounts, min_stake, max_stake, platform_fee_percent, new_server_authority)
+            process_update_game_params(
+                program_id,
+                accounts,
+                min_stake,
+                max_stake,
+                platform_fee_percent,
+                new_platform_fee_wallet,
+                heartbeat_timeout,
+            )
         },
         SolanaIoInstruction::ForceCleanup { player } => {
             msg!("Server forcing cleanup for player: {}", player);
             process_force_cleanup(program_id, accounts, player)
         },
+        SolanaIoInstruction::WithdrawFees { amount } => {
+            msg!("Withdrawing {} lamports of platform fees", amount);
+            process_withdraw_fees(program_id, accounts, amount)
+        },
+        SolanaIoInstruction::ProposeAuthority { role, new_key } => {
+            msg!("Proposing new {:?} authority: {}", role, new_key);
+            process_propose_authority(program_id, accounts, role, new_key)
+        },
+        SolanaIoInstruction::AcceptAuthority { role } => {
+            msg!("Accepting {:?} authority", role);
+            process_accept_authority(program_id, accounts, role)
+        },
+        SolanaIoInstruction::CancelProposal { role } => {
+            msg!("Cancelling {:?} authority proposal", role);
+            process_cancel_proposal(program_id, accounts, role)
+        },
+        SolanaIoInstruction::CreateMultisig { role, signers, threshold } => {
+            msg!("Creating {:?} multisig: {} of {}", role, threshold, signers.len());
+            process_create_multisig(program_id, accounts, role, signers, threshold)
+        },
+        SolanaIoInstruction::SetMultisigSigners { role, signers, threshold } => {
+            msg!("Updating {:?} multisig: {} of {}", role, threshold, signers.len());
+            process_set_multisig_signers(program_id, accounts, role, signers, threshold)
+        },
+        SolanaIoInstruction::SetPaused { joins, gameplay, cashouts } => {
+            msg!("Setting pause flags: joins={}, gameplay={}, cashouts={}", joins, gameplay, cashouts);
+            process_set_paused(program_id, accounts, joins, gameplay, cashouts)
+        },
+        SolanaIoInstruction::Heartbeat => {
+            process_heartbeat(program_id, accounts)
+        },
+        SolanaIoInstruction::EmergencyWithdraw => {
+            msg!("Player emergency withdraw");
+            process_emergency_withdraw(program_id, accounts)
+        },
+        SolanaIoInstruction::CreateRoom { room_id } => {
+            msg!("Creating room {}", room_id);
+            process_create_room(program_id, accounts, room_id)
+        },
+        SolanaIoInstruction::UpdateRoomParams { room_id, min_stake, max_stake } => {
+            msg!("Updating room {} parameters", room_id);
+            process_update_room_params(program_id, accounts, room_id, min_stake, max_stake)
+        },
+        SolanaIoInstruction::CollectRoomFees => {
+            msg!("Collecting room fees");
+            process_collect_room_fees(program_id, accounts)
+        },
+        SolanaIoInstruction::BatchUpdatePlayerValues { events } => {
+            msg!("Updating player values for {} eat events", events.len());
+            process_batch_update_player_values(program_id, accounts, events)
+        },
+        SolanaIoInstruction::ClosePlayerState => {
+            msg!("Closing player state");
+            process_close_player_state(program_id, accounts)
+        },
+        SolanaIoInstruction::CheckSolvency => {
+            process_check_solvency(program_id, accounts)
+        },
+        SolanaIoInstruction::ForceRefund { player, charge_fee } => {
+            msg!("Server refunding player: {} (fee: {})", player, charge_fee);
+            process_force_refund(program_id, accounts, player, charge_fee)
+        },
+        SolanaIoInstruction::PartialCashOut { ticket } => {
+            msg!("Player partially cashing out {} lamports with ticket nonce: {}", ticket.amount, ticket.nonce);
+            process_partial_cash_out(program_id, accounts, ticket)
+        },
+        SolanaIoInstruction::TopUp { amount } => {
+            msg!("Player topping up stake with: {} lamports", amount);
+            process_top_up(program_id, accounts, amount)
+        },
+        SolanaIoInstruction::Reconcile { room_id, player_count, start, finish } => {
+            msg!("Reconciling room {}: {} player states", room_id, player_count);
+            process_reconcile(program_id, accounts, room_id, player_count, start, finish)
+        },
     }
 }
 
@@ -181,32 +632,18 @@ fn process_initialize_game(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     server_authority: Pubkey,
+    admin_authority: Pubkey,
+    platform_fee_wallet: Pubkey,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    
-    let initializer = next_account_info(accounts_iter)?;
-    let game_account = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
-    let rent_account = next_account_info(accounts_iter)?;
-    
-    if !initializer.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
-    
-    // Weryfikacja PDA dla globalnej gry
-    let (expected_game_pubkey, bump_seed) = Pubkey::find_program_address(
-        &[b"global_game"],
-        program_id,
-    );
-    
-    if expected_game_pubkey != *game_account.key {
-        return Err(ProgramError::InvalidArgument);
-    }
-    
-    // Sprawdź czy gra już jest zainicjalizowana
-    if !game_account.data_is_empty() {
-        return Err(ProgramError::AccountAlreadyInitialized);
-    }
+    let InitializeGameAccounts {
+        initializer,
+        game_account,
+        fee_vault_account,
+        system_program,
+        rent_account,
+        game_bump: bump_seed,
+        fee_vault_bump,
+    } = InitializeGameAccounts::parse(program_id, accounts)?;
     
     // Oblicz czynsz
     let rent = Rent::from_account_info(rent_account)?;
@@ -227,78 +664,112 @@ fn process_initialize_game(
             game_account.clone(),
             system_program.clone(),
         ],
-        &[&[b"global_game".as_ref(), &[bump_seed]]],
+        &[&[GLOBAL_GAME_SEED, &[bump_seed]]],
+    )?;
+    
+    // Utwórz skarbiec prowizji - konto bez danych, tylko lamporty
+    invoke_signed(
+        &system_instruction::create_account(
+            initializer.key,
+            fee_vault_account.key,
+            rent.minimum_balance(0),
+            0,
+            program_id,
+        ),
+        &[
+            initializer.clone(),
+            fee_vault_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[FEE_VAULT_SEED, &[fee_vault_bump]]],
     )?;
     
-    // Inicjalizuj dane gry z server authority
+    // Inicjalizuj dane gry z server i admin authority
     let clock = Clock::get()?;
-    let game = GlobalGame::new(clock.unix_timestamp, server_authority);
+    let game = GlobalGame::new(
+        clock.unix_timestamp,
+        server_authority,
+        admin_authority,
+        platform_fee_wallet,
+        bump_seed,
+        fee_vault_bump,
+    );
     
     game.to_account_data(&mut game_account.data.borrow_mut())?;
     
-    msg!("Global game initialized successfully with server authority: {}", server_authority);
+    SolanaIoEvent::GameInitialized {
+        server_authority,
+        admin_authority,
+        platform_fee_wallet,
+    }
+    .emit();
+    
+    msg!("Global game initialized successfully with server authority: {}, admin authority: {}, fee wallet: {}",
+         server_authority, admin_authority, platform_fee_wallet);
     Ok(())
 }
 
 fn process_join_game(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    room_id: u32,
     stake_amount: u64,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    
-    let player_account = next_account_info(accounts_iter)?;
-    let player_state_account = next_account_info(accounts_iter)?;
-    let game_account = next_account_info(accounts_iter)?;
-    let system_program = next_account_info(accounts_iter)?;
-    let rent_account = next_account_info(accounts_iter)?;
-    
-    if !player_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    let JoinGameAccounts {
+        player_account,
+        player_state_account,
+        room_account,
+        system_program,
+        rent_account,
+        game,
+        mut room,
+        player_state,
+        player_state_bump: bump_seed,
+        ..
+    } = JoinGameAccounts::parse(program_id, accounts, room_id)?;
+    
+    if game.paused_joins {
+        msg!("Joins are paused");
+        return Err(SolanaIoError::ProgramPaused.into());
     }
     
-    // Załaduj dane gry
-    let mut game = GlobalGame::from_account_data(&game_account.data.borrow())?;
-    
-    // Walidacja stawki
-    if stake_amount < game.min_stake || stake_amount > game.max_stake {
-        msg!("Invalid stake amount: {} (min: {}, max: {})", 
-             stake_amount, game.min_stake, game.max_stake);
-        return Err(ProgramError::InvalidArgument);
+    // Walidacja stawki - limity pokoju
+    if stake_amount < room.min_stake {
+        msg!("Invalid stake amount: {} (min: {})", stake_amount, room.min_stake);
+        return Err(SolanaIoError::StakeBelowMinimum.into());
     }
-    
-    // Weryfikacja PDA dla stanu gracza
-    let (expected_player_state_pubkey, bump_seed) = Pubkey::find_program_address(
-        &[b"player_state", player_account.key.as_ref()],
-        program_id,
-    );
-    
-    if expected_player_state_pubkey != *player_state_account.key {
-        return Err(ProgramError::InvalidArgument);
+
+    if stake_amount > room.max_stake {
+        msg!("Invalid stake amount: {} (max: {})", stake_amount, room.max_stake);
+        return Err(SolanaIoError::StakeAboveMaximum.into());
     }
     
     // Sprawdź czy gracz już ma konto
     let mut is_rejoining = false;
+    let mut is_new_to_room = true;
     let mut existing_value = 0u64;
+    let current_value;
     
-    if !player_state_account.data_is_empty() {
+    if let Some(mut player_state) = player_state {
         // Gracz już ma konto - sprawdź stan
-        let mut player_state = PlayerState::try_from_slice(&player_state_account.data.borrow())?;
-        
         if player_state.is_active {
-            msg!("Player is already active in the game");
-            return Err(ProgramError::AccountAlreadyInitialized);
+            msg!("Player is already active in room {}", player_state.room_id);
+            return Err(SolanaIoError::PlayerAlreadyActive.into());
         }
         
-        // Gracz może ponownie dołączyć
+        // Gracz może ponownie dołączyć - także do innego pokoju
         is_rejoining = true;
+        is_new_to_room = player_state.room_id != room_id;
         existing_value = player_state.current_value;
         
         // Aktualizuj stan gracza
+        player_state.room_id = room_id;
         player_state.is_active = true;
         player_state.stake_amount += stake_amount;
         player_state.current_value += stake_amount;
+        player_state.session_stake = stake_amount;
         player_state.joined_at = Clock::get()?.unix_timestamp;
+        current_value = player_state.current_value;
         
         // Zapisz zaktualizowany stan
         player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
@@ -321,167 +792,307 @@ fn process_join_game(
                 player_state_account.clone(),
                 system_program.clone(),
             ],
-            &[&[b"player_state".as_ref(), player_account.key.as_ref(), &[bump_seed]]],
+            &[&[PLAYER_STATE_SEED, player_account.key.as_ref(), &[bump_seed]]],
         )?;
         
-        // Utwórz nowy stan gracza
+        // Nonce nowego konta: slot w starszych bitach, licznik dołączeń pokoju w młodszych -
+        // po zamknięciu i ponownym dołączeniu (nawet w tym samym slocie) bilety
+        // wystawione dla poprzedniego konta nie pasują
+        let clock = Clock::get()?;
         let player_state = PlayerState {
             pubkey: *player_account.key,
             stake_amount,
             current_value: stake_amount,
             is_active: true,
-            joined_at: Clock::get()?.unix_timestamp,
+            joined_at: clock.unix_timestamp,
             last_cashout: 0,
             total_earned: 0,
+            bump: bump_seed,
+            cashout_nonce: clock.slot << 32 | u64::from(room.total_players),
+            room_id,
+            session_stake: stake_amount,
         };
+        current_value = stake_amount;
         
         player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
     }
     
-    // Transfer stawki do puli gry
+    // Transfer stawki do puli pokoju
     invoke(
         &system_instruction::transfer(
             player_account.key,
-            game_account.key,
+            room_account.key,
             stake_amount,
         ),
         &[
             player_account.clone(),
-            game_account.clone(),
+            room_account.clone(),
             system_program.clone(),
         ],
     )?;
     
-    // Zaktualizuj dane gry
-    game.total_pool += stake_amount;
-    if !is_rejoining {
-        game.active_players += 1;
-        game.total_players += 1;
-    } else {
-        game.active_players += 1;
+    // Zaktualizuj dane pokoju - konfiguracja gry zostaje nietknięta
+    room.total_pool += stake_amount;
+    room.active_players += 1;
+    if is_new_to_room {
+        room.total_players += 1;
+    }
+    if is_rejoining {
         msg!("Player rejoining with existing value: {} + new stake: {} = total: {}", 
              existing_value, stake_amount, existing_value + stake_amount);
     }
     
-    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    SolanaIoEvent::PlayerJoined {
+        player: *player_account.key,
+        room_id,
+        stake_amount,
+        current_value,
+        rejoined: is_rejoining,
+    }
+    .emit();
     
-    msg!("Player {} {} with stake: {} lamports", 
+    msg!("Player {} {} room {} with stake: {} lamports", 
          player_account.key, 
          if is_rejoining { "rejoined" } else { "joined" },
+         room_id,
          stake_amount);
     Ok(())
 }
 
-fn process_update_player_value(
-    _program_id: &Pubkey,
+fn process_top_up(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    _player: Pubkey,
-    _eaten_player: Pubkey,
-    eaten_value: u64,
+    amount: u64,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
+    let TopUpAccounts {
+        player_account,
+        player_state_account,
+        room_account,
+        system_program,
+        game,
+        mut room,
+        mut player_state,
+    } = TopUpAccounts::parse(program_id, accounts)?;
+    
+    // Dopłata to nowa stawka w puli - wstrzymana razem z dołączeniami
+    if game.paused_joins {
+        msg!("Joins are paused");
+        return Err(SolanaIoError::ProgramPaused.into());
+    }
+    
+    if !player_state.is_active {
+        return Err(SolanaIoError::PlayerNotActive.into());
+    }
+    
+    if amount == 0 {
+        return Err(SolanaIoError::InvalidTopUpAmount.into());
+    }
+    
+    let session_stake = player_state.session_stake
+        .checked_add(amount)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    if session_stake > room.max_stake {
+        msg!("Session stake after top-up: {} (max: {})", session_stake, room.max_stake);
+        return Err(SolanaIoError::StakeAboveMaximum.into());
+    }
+    
+    invoke(
+        &system_instruction::transfer(
+            player_account.key,
+            room_account.key,
+            amount,
+        ),
+        &[
+            player_account.clone(),
+            room_account.clone(),
+            system_program.clone(),
+        ],
+    )?;
+    
+    player_state.session_stake = session_stake;
+    player_state.stake_amount += amount;
+    player_state.current_value += amount;
+    player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
     
-    let authority_account = next_account_info(accounts_iter)?;
-    let player_state_account = next_account_info(accounts_iter)?;
-    let eaten_player_state_account = next_account_info(accounts_iter)?;
-    let game_account = next_account_info(accounts_iter)?;
+    room.total_pool += amount;
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
     
-    if !authority_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    // Serwer zwiększa masę gracza w grze po tym zdarzeniu
+    SolanaIoEvent::PlayerToppedUp {
+        player: *player_account.key,
+        room_id: room.room_id,
+        amount,
+        session_stake,
+        current_value: player_state.current_value,
     }
+    .emit();
     
-    // Załaduj grę i sprawdź czy to autoryzowany serwer
-    let game = GlobalGame::from_account_data(&game_account.data.borrow())?;
+    msg!("Player topped up: {} lamports, new value: {}", amount, player_state.current_value);
+    Ok(())
+}
+
+fn process_update_player_value(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    player: Pubkey,
+    eaten_player: Pubkey,
+    eaten_value: u64,
+) -> ProgramResult {
+    let UpdatePlayerValueAccounts {
+        authority_account,
+        player_state_account,
+        eaten_player_state_account,
+        room_account,
+        multisig_signers,
+        game,
+        mut room,
+        mut player_state,
+        mut eaten_player_state,
+        ..
+    } = UpdatePlayerValueAccounts::parse(program_id, accounts, &player, &eaten_player)?;
     
     // Weryfikacja authority - tylko zapisany serwer może aktualizować
     if *authority_account.key != game.server_authority {
         msg!("Unauthorized: Only server authority can update player values");
         msg!("Expected: {}, Got: {}", game.server_authority, authority_account.key);
-        return Err(ProgramError::InvalidAccountData);
+        return Err(SolanaIoError::UnauthorizedServerAuthority.into());
+    }
+    check_authority_signature(program_id, authority_account, multisig_signers)?;
+    
+    if game.paused_gameplay {
+        msg!("Gameplay is paused");
+        return Err(SolanaIoError::ProgramPaused.into());
+    }
+    
+    // Po awarii serwer musi najpierw wysłać Heartbeat - nie działa na utraconym stanie
+    if game.is_server_stale(Clock::get()?.unix_timestamp) {
+        msg!("Server heartbeat is stale (last: {})", game.last_heartbeat);
+        return Err(SolanaIoError::ServerStale.into());
+    }
+    
+    if !player_state.is_active {
+        return Err(SolanaIoError::PlayerNotActive.into());
     }
     
-    // Załaduj stany graczy
-    let mut player_state = PlayerState::try_from_slice(&player_state_account.data.borrow())?;
-    let mut eaten_player_state = PlayerState::try_from_slice(&eaten_player_state_account.data.borrow())?;
+    if !eaten_player_state.is_active {
+        return Err(SolanaIoError::EatenPlayerNotActive.into());
+    }
     
-    if !player_state.is_active || !eaten_player_state.is_active {
-        return Err(ProgramError::InvalidAccountData);
+    // Przenosimy dokładnie to, co zjedzony gracz ma on-chain - serwer nie może wykreować wartości
+    let moved_value = eaten_player_state.current_value;
+    if eaten_value != moved_value {
+        msg!("Eaten value mismatch: argument {} != on-chain {}", eaten_value, moved_value);
+        return Err(SolanaIoError::EatenValueMismatch.into());
     }
     
     // Transfer wartości - WAŻNE: dodaj wartość do gracza który zjadł
-    player_state.current_value += eaten_value;
+    player_state.current_value = player_state.current_value
+        .checked_add(moved_value)
+        .ok_or(SolanaIoError::MathOverflow)?;
     eaten_player_state.current_value = 0;
     eaten_player_state.is_active = false;
     
     msg!("Player gained {} lamports from eating. New value: {} lamports", 
-         eaten_value, player_state.current_value);
+         moved_value, player_state.current_value);
     
     // Zapisz zmiany
     player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
     eaten_player_state.serialize(&mut &mut eaten_player_state_account.data.borrow_mut()[..])?;
     
     // Zaktualizuj liczbę aktywnych graczy
-    let mut game = GlobalGame::from_account_data(&game_account.data.borrow())?;
-    game.active_players = game.active_players.saturating_sub(1);
-    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    room.active_players = room.active_players.saturating_sub(1);
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    SolanaIoEvent::PlayerEaten {
+        room_id: room.room_id,
+        player,
+        eaten_player,
+        eaten_value: moved_value,
+        new_value: player_state.current_value,
+    }
+    .emit();
     
     msg!("Player value updated successfully by authorized server");
     Ok(())
 }
 
 fn process_cash_out(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
+    ticket: CashOutTicket,
+    close: bool,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    
-    let player_account = next_account_info(accounts_iter)?;
-    let player_state_account = next_account_info(accounts_iter)?;
-    let game_account = next_account_info(accounts_iter)?;
-    let platform_fee_account = next_account_info(accounts_iter)?;
-    
-    const PLATFORM_WALLET: &str = "FEEfBE29dqRgC8qMv6f9YXTSNbX7LMN3Reo3UsYdoUd8";
-    let platform_pubkey = Pubkey::try_from(PLATFORM_WALLET).unwrap();
+    let CashOutAccounts {
+        player_account,
+        player_state_account,
+        room_account,
+        instructions_sysvar,
+        game,
+        mut room,
+        mut player_state,
+        server_multisig,
+        ..
+    } = CashOutAccounts::parse(program_id, accounts)?;
+    
+    if game.paused_cashouts {
+        msg!("Cash outs are paused");
+        return Err(SolanaIoError::ProgramPaused.into());
+    }
     
-    if *platform_fee_account.key != platform_pubkey {
-        return Err(ProgramError::InvalidArgument);
+    if !player_state.is_active {
+        return Err(SolanaIoError::PlayerNotActive.into());
     }
     
-    if !player_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    if player_state.current_value == 0 {
+        return Err(SolanaIoError::NothingToCashOut.into());
     }
     
-    // Załaduj stan gracza
-    let mut player_state = PlayerState::try_from_slice(&player_state_account.data.borrow())?;
+    // Bilet serwera: wypłata tylko tyle, ile widzi silnik gry - jeśli zjedzenie
+    // jest jeszcze w drodze, wartości się nie zgodzą i wypłata zostanie odrzucona
+    let signatures = ed25519_signatures(instructions_sysvar)?;
+    check_ticket_signers(
+        program_id,
+        CashOutTicket::KIND,
+        &ticket.body(),
+        &game.server_authority,
+        server_multisig.as_ref(),
+        &signatures,
+    )?;
+    
+    if ticket.player != player_state.pubkey || ticket.amount != player_state.current_value {
+        msg!("Ticket for {} lamports, on-chain value: {}", ticket.amount, player_state.current_value);
+        return Err(SolanaIoError::CashOutTicketMismatch.into());
+    }
     
-    if !player_state.is_active {
-        return Err(ProgramError::InvalidAccountData);
+    if ticket.nonce != player_state.cashout_nonce {
+        msg!("Ticket nonce {}, expected {}", ticket.nonce, player_state.cashout_nonce);
+        return Err(SolanaIoError::InvalidTicketNonce.into());
     }
     
-    if player_state.current_value == 0 {
-        return Err(ProgramError::InsufficientFunds);
+    if Clock::get()?.unix_timestamp > ticket.expiry {
+        return Err(SolanaIoError::CashOutTicketExpired.into());
     }
+    player_state.cashout_nonce += 1;
     
-    // Załaduj grę
-    let mut game = GlobalGame::from_account_data(&game_account.data.borrow())?;
+    // Cała wartość gracza schodzi z puli: wypłata do gracza, prowizja do `fee_collected`
+    check_room_solvency(room_account, &room, player_state.current_value)?;
     
     // Oblicz prowizję
     let platform_fee = player_state.current_value * game.platform_fee_percent as u64 / 100;
     let player_payout = player_state.current_value - platform_fee;
     
-    // Transfer prowizji
-    if platform_fee > 0 {
-        **game_account.try_borrow_mut_lamports()? = 
-            game_account.lamports().saturating_sub(platform_fee);
-        **platform_fee_account.try_borrow_mut_lamports()? = 
-            platform_fee_account.lamports().saturating_add(platform_fee);
-    }
+    // Prowizja zostaje na koncie pokoju - CollectRoomFees przenosi ją do skarbca.
+    // Dzięki temu wypłaty nie blokują zapisu konfiguracji ani skarbca.
     
     // Transfer wypłaty do gracza
-    **game_account.try_borrow_mut_lamports()? = 
-        game_account.lamports().saturating_sub(player_payout);
-    **player_account.try_borrow_mut_lamports()? = 
-        player_account.lamports().saturating_add(player_payout);
+    **room_account.try_borrow_mut_lamports()? = room_account.lamports()
+        .checked_sub(player_payout)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    **player_account.try_borrow_mut_lamports()? = player_account.lamports()
+        .checked_add(player_payout)
+        .ok_or(SolanaIoError::MathOverflow)?;
     
     // Zaktualizuj stan gracza
     let clock = Clock::get()?;
@@ -491,43 +1102,164 @@ fn process_cash_out(
     let final_value = player_state.current_value;
     player_state.current_value = 0;
     
-    player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
-    
-    // Zaktualizuj grę
-    game.total_pool = game.total_pool.saturating_sub(final_value);
-    game.active_players = game.active_players.saturating_sub(1);
-    game.platform_fee_collected += platform_fee;
+    if !close {
+        player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
+    }
     
-    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    // Zaktualizuj pokój i jego licznik prowizji
+    room.total_pool = room.total_pool.saturating_sub(final_value);
+    room.active_players = room.active_players.saturating_sub(1);
+    room.fee_collected = room.fee_collected
+        .checked_add(platform_fee)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    SolanaIoEvent::PlayerCashedOut {
+        player: player_state.pubkey,
+        room_id: room.room_id,
+        value: final_value,
+        payout: player_payout,
+        fee: platform_fee,
+        emergency: false,
+    }
+    .emit();
     
     msg!("Player cashed out: {} lamports (fee: {} lamports)", player_payout, platform_fee);
+    
+    if close {
+        close_player_state(player_state_account, player_account, &mut player_state)?;
+    }
     Ok(())
 }
 
-fn process_update_game_params(
-    _program_id: &Pubkey,
+fn process_partial_cash_out(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    min_stake: Option<u64>,
-    max_stake: Option<u64>,
-    platform_fee_percent: Option<u8>,
-    new_server_authority: Option<Pubkey>,
+    ticket: PartialCashOutTicket,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
+    let CashOutAccounts {
+        player_account,
+        player_state_account,
+        room_account,
+        instructions_sysvar,
+        game,
+        mut room,
+        mut player_state,
+        server_multisig,
+        ..
+    } = CashOutAccounts::parse(program_id, accounts)?;
+    
+    if game.paused_cashouts {
+        msg!("Cash outs are paused");
+        return Err(SolanaIoError::ProgramPaused.into());
+    }
+    
+    if !player_state.is_active {
+        return Err(SolanaIoError::PlayerNotActive.into());
+    }
+    
+    if ticket.amount == 0 {
+        return Err(SolanaIoError::NothingToCashOut.into());
+    }
+    
+    // Bilet potwierdza wartość widzianą przez silnik gry - serwer zmniejsza masę
+    // gracza o `amount` dopiero po zdarzeniu PlayerPartiallyCashedOut
+    let signatures = ed25519_signatures(instructions_sysvar)?;
+    check_ticket_signers(
+        program_id,
+        PartialCashOutTicket::KIND,
+        &ticket.body(),
+        &game.server_authority,
+        server_multisig.as_ref(),
+        &signatures,
+    )?;
     
-    let admin_account = next_account_info(accounts_iter)?;
-    let game_account = next_account_info(accounts_iter)?;
+    if ticket.player != player_state.pubkey || ticket.value != player_state.current_value {
+        msg!("Ticket for value {} lamports, on-chain value: {}", ticket.value, player_state.current_value);
+        return Err(SolanaIoError::CashOutTicketMismatch.into());
+    }
+    
+    if ticket.nonce != player_state.cashout_nonce {
+        msg!("Ticket nonce {}, expected {}", ticket.nonce, player_state.cashout_nonce);
+        return Err(SolanaIoError::InvalidTicketNonce.into());
+    }
     
-    if !admin_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    if Clock::get()?.unix_timestamp > ticket.expiry {
+        return Err(SolanaIoError::CashOutTicketExpired.into());
     }
     
-    let mut game = GlobalGame::from_account_data(&game_account.data.borrow())?;
+    // Wypłata całości to CashOut - gracz w grze musi mieć co najmniej minimalną stawkę
+    let remaining_value = player_state.current_value
+        .checked_sub(ticket.amount)
+        .filter(|remaining| *remaining >= room.min_stake)
+        .ok_or_else(|| {
+            msg!("Remaining value below room minimum stake: {}", room.min_stake);
+            SolanaIoError::PartialCashOutTooLarge
+        })?;
+    player_state.cashout_nonce += 1;
+    
+    check_room_solvency(room_account, &room, ticket.amount)?;
+    
+    let platform_fee = ticket.amount * game.platform_fee_percent as u64 / 100;
+    let player_payout = ticket.amount - platform_fee;
+    
+    // Prowizja zostaje na koncie pokoju jak przy CashOut
+    **room_account.try_borrow_mut_lamports()? = room_account.lamports()
+        .checked_sub(player_payout)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    **player_account.try_borrow_mut_lamports()? = player_account.lamports()
+        .checked_add(player_payout)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    
+    // Gracz zostaje aktywny - liczba graczy w pokoju się nie zmienia
+    player_state.last_cashout = Clock::get()?.unix_timestamp;
+    player_state.total_earned += player_payout;
+    player_state.current_value = remaining_value;
+    player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
+    
+    room.total_pool -= ticket.amount;
+    room.fee_collected = room.fee_collected
+        .checked_add(platform_fee)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    SolanaIoEvent::PlayerPartiallyCashedOut {
+        player: player_state.pubkey,
+        room_id: room.room_id,
+        amount: ticket.amount,
+        payout: player_payout,
+        fee: platform_fee,
+        remaining_value,
+    }
+    .emit();
     
-    // Tylko server authority może aktualizować parametry
-    if *admin_account.key != game.server_authority {
-        msg!("Unauthorized: Only server authority can update game params");
-        return Err(ProgramError::InvalidAccountData);
+    msg!("Player partially cashed out: {} lamports (fee: {} lamports), remaining value: {}",
+         player_payout, platform_fee, remaining_value);
+    Ok(())
+}
+
+fn process_update_game_params(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    min_stake: Option<u64>,
+    max_stake: Option<u64>,
+    platform_fee_percent: Option<u8>,
+    new_platform_fee_wallet: Option<Pubkey>,
+    heartbeat_timeout: Option<i64>,
+) -> ProgramResult {
+    let UpdateGameParamsAccounts {
+        admin_account,
+        game_account,
+        multisig_signers,
+        mut game,
+    } = UpdateGameParamsAccounts::parse(program_id, accounts)?;
+    
+    // Tylko admin authority może aktualizować parametry - klucz serwera służy wyłącznie do rozgrywki
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can update game params");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
     }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
     
     if let Some(min) = min_stake {
         game.min_stake = min;
@@ -537,20 +1269,42 @@ fn process_update_game_params(
         game.max_stake = max;
     }
     
+    // Limity stawek to domyślne wartości dla nowych pokoi - istniejące zmienia UpdateRoomParams
+    if game.min_stake > game.max_stake {
+        msg!("Invalid stake range: min {} > max {}", game.min_stake, game.max_stake);
+        return Err(SolanaIoError::InvalidStakeRange.into());
+    }
+    
     if let Some(fee) = platform_fee_percent {
         if fee > 10 { // Maksymalnie 10% prowizji
-            return Err(ProgramError::InvalidArgument);
+            return Err(SolanaIoError::FeePercentTooHigh.into());
         }
         game.platform_fee_percent = fee;
     }
     
-    if let Some(new_authority) = new_server_authority {
-        game.server_authority = new_authority;
-        msg!("Server authority updated to: {}", new_authority);
+    if let Some(new_wallet) = new_platform_fee_wallet {
+        game.platform_fee_wallet = new_wallet;
+        msg!("Platform fee wallet updated to: {}", new_wallet);
+    }
+    
+    if let Some(timeout) = heartbeat_timeout {
+        if timeout <= 0 {
+            return Err(SolanaIoError::InvalidHeartbeatTimeout.into());
+        }
+        game.heartbeat_timeout = timeout;
     }
     
     game.to_account_data(&mut game_account.data.borrow_mut())?;
     
+    SolanaIoEvent::ParamsUpdated {
+        min_stake: game.min_stake,
+        max_stake: game.max_stake,
+        platform_fee_percent: game.platform_fee_percent,
+        platform_fee_wallet: game.platform_fee_wallet,
+        heartbeat_timeout: game.heartbeat_timeout,
+    }
+    .emit();
+    
     msg!("Game parameters updated");
     Ok(())
 }
@@ -561,51 +1315,34 @@ fn process_force_cleanup(
     accounts: &[AccountInfo],
     player_pubkey: Pubkey,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
+    let ForceCleanupAccounts {
+        authority_account,
+        player_state_account,
+        room_account,
+        multisig_signers,
+        game,
+        mut room,
+        mut player_state,
+        ..
+    } = ForceCleanupAccounts::parse(program_id, accounts, &player_pubkey)?;
     
-    let authority_account = next_account_info(accounts_iter)?;
-    let player_state_account = next_account_info(accounts_iter)?;
-    let game_account = next_account_info(accounts_iter)?;
-    let platform_fee_account = next_account_info(accounts_iter)?;
-    let player_account = next_account_info(accounts_iter)?;
-    
-    const PLATFORM_WALLET: &str = "FEEfBE29dqRgC8qMv6f9YXTSNbX7LMN3Reo3UsYdoUd8";
-    let platform_pubkey = Pubkey::try_from(PLATFORM_WALLET).unwrap();
+    if *authority_account.key != game.server_authority {
+        msg!("Unauthorized: Only server authority can force cash out");
+        return Err(SolanaIoError::UnauthorizedServerAuthority.into());
+    }
+    check_authority_signature(program_id, authority_account, multisig_signers)?;
     
-    if *platform_fee_account.key != platform_pubkey {
-        return Err(ProgramError::InvalidArgument);
+    if game.paused_gameplay {
+        msg!("Gameplay is paused");
+        return Err(SolanaIoError::ProgramPaused.into());
     }
     
-    if !authority_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    // Po awarii serwer musi najpierw wysłać Heartbeat - nie działa na utraconym stanie
+    if game.is_server_stale(Clock::get()?.unix_timestamp) {
+        msg!("Server heartbeat is stale (last: {})", game.last_heartbeat);
+        return Err(SolanaIoError::ServerStale.into());
     }
     
-    // Załaduj grę i sprawdź authority
-    let mut game = GlobalGame::from_account_data(&game_account.data.borrow())?;
-    
-    if *authority_account.key != game.server_authority {
-        msg!("Unauthorized: Only server authority can force cash out");
-        return Err(ProgramError::InvalidAccountData);
-    }
-    
-    // Sprawdź czy player account jest poprawny
-    if *player_account.key != player_pubkey {
-        return Err(ProgramError::InvalidArgument);
-    }
-    
-    // Sprawdź PDA gracza
-    let (expected_player_state_pubkey, _) = Pubkey::find_program_address(
-        &[b"player_state", player_pubkey.as_ref()],
-        program_id,
-    );
-    
-    if expected_player_state_pubkey != *player_state_account.key {
-        return Err(ProgramError::InvalidArgument);
-    }
-    
-    // Załaduj stan gracza
-    let mut player_state = PlayerState::try_from_slice(&player_state_account.data.borrow())?;
-    
     // WAŻNE: Force cash out TYLKO czyści stan - NIE wypłaca pieniędzy!
     // To jest tylko do usuwania "ghost" graczy z blockchain
     
@@ -634,15 +1371,896 @@ fn process_force_cleanup(
     
     player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
     
-    // Aktualizuj grę - zmniejsz liczbę aktywnych graczy
-    game.active_players = game.active_players.saturating_sub(1);
+    // Aktualizuj pokój - zmniejsz liczbę aktywnych graczy
+    room.active_players = room.active_players.saturating_sub(1);
     
     // WAŻNE: NIE odejmujemy z total_pool bo te środki już zostały przeniesione
     // (gracz został zjedzony lub cash out był już wykonany)
     
-    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    SolanaIoEvent::PlayerForceCleaned {
+        player: player_pubkey,
+        room_id: room.room_id,
+        lost_value,
+    }
+    .emit();
     
     msg!("Server forced cleanup for ghost player {} (lost {} lamports)", 
          player_pubkey, lost_value);
     Ok(())
-}
\ No newline at end of file
+}
+
+fn process_force_refund(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    player_pubkey: Pubkey,
+    charge_fee: bool,
+) -> ProgramResult {
+    let ForceRefundAccounts {
+        authority_account,
+        player_account,
+        player_state_account,
+        room_account,
+        multisig_signers,
+        game,
+        mut room,
+        mut player_state,
+        ..
+    } = ForceRefundAccounts::parse(program_id, accounts, &player_pubkey)?;
+    
+    if *authority_account.key != game.server_authority {
+        msg!("Unauthorized: Only server authority can force refund");
+        return Err(SolanaIoError::UnauthorizedServerAuthority.into());
+    }
+    check_authority_signature(program_id, authority_account, multisig_signers)?;
+    
+    // Zwrot to wypłata - podlega wstrzymaniu wypłat, nie rozgrywki
+    if game.paused_cashouts {
+        msg!("Cash outs are paused");
+        return Err(SolanaIoError::ProgramPaused.into());
+    }
+    
+    let clock = Clock::get()?;
+    if game.is_server_stale(clock.unix_timestamp) {
+        msg!("Server heartbeat is stale (last: {})", game.last_heartbeat);
+        return Err(SolanaIoError::ServerStale.into());
+    }
+    
+    if !player_state.is_active {
+        return Err(SolanaIoError::PlayerNotActive.into());
+    }
+    
+    if player_state.current_value == 0 {
+        return Err(SolanaIoError::NothingToCashOut.into());
+    }
+    
+    let value = player_state.current_value;
+    check_room_solvency(room_account, &room, value)?;
+    
+    let fee = if charge_fee {
+        value * game.platform_fee_percent as u64 / 100
+    } else {
+        0
+    };
+    let payout = value - fee;
+    
+    // Prowizja zostaje na koncie pokoju jak przy CashOut - wychodzi tylko zwrot
+    **room_account.try_borrow_mut_lamports()? = room_account.lamports()
+        .checked_sub(payout)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    **player_account.try_borrow_mut_lamports()? = player_account.lamports()
+        .checked_add(payout)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    
+    // Bilet wystawiony przed awarią nie może już zadziałać
+    player_state.cashout_nonce += 1;
+    player_state.is_active = false;
+    player_state.last_cashout = clock.unix_timestamp;
+    player_state.total_earned += payout;
+    player_state.current_value = 0;
+    player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
+    
+    room.total_pool -= value;
+    room.active_players = room.active_players.saturating_sub(1);
+    room.fee_collected = room.fee_collected
+        .checked_add(fee)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    SolanaIoEvent::PlayerRefunded {
+        player: player_pubkey,
+        room_id: room.room_id,
+        value,
+        payout,
+        fee,
+    }
+    .emit();
+    
+    msg!("Player refunded: {} lamports (fee: {} lamports)", payout, fee);
+    Ok(())
+}
+
+fn process_withdraw_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let WithdrawFeesAccounts {
+        admin_account,
+        game_account,
+        fee_vault_account,
+        recipient_account,
+        multisig_signers,
+        mut game,
+    } = WithdrawFeesAccounts::parse(program_id, accounts)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can withdraw fees");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    // Wypłacamy tylko zebrane prowizje - pula graczy leży na koncie gry, nie w skarbcu
+    if amount > game.platform_fee_collected {
+        msg!("Requested {} lamports, accrued fees: {}", amount, game.platform_fee_collected);
+        return Err(SolanaIoError::InsufficientAccruedFees.into());
+    }
+    
+    // Skarbiec musi pokrywać czynsz i wszystkie zapisane prowizje, zanim cokolwiek wypłacimy
+    check_fee_vault_solvency(fee_vault_account, &game)?;
+    
+    **fee_vault_account.try_borrow_mut_lamports()? = fee_vault_account.lamports()
+        .checked_sub(amount)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    **recipient_account.try_borrow_mut_lamports()? = recipient_account.lamports()
+        .checked_add(amount)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    
+    game.platform_fee_collected -= amount;
+    game.total_fees_withdrawn = game.total_fees_withdrawn
+        .checked_add(amount)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    msg!("Withdrew {} lamports of fees to {}", amount, recipient_account.key);
+    Ok(())
+}
+
+fn process_propose_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: AuthorityRole,
+    new_key: Pubkey,
+) -> ProgramResult {
+    let AuthorityTransferAccounts {
+        authority_account: admin_account,
+        game_account,
+        multisig_signers,
+        mut game,
+    } = AuthorityTransferAccounts::parse(program_id, accounts)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can propose authority changes");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    // Nowa propozycja nadpisuje poprzednią dla tej samej roli
+    *game.pending_authority_mut(role) = Some(new_key);
+    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    msg!("{:?} authority proposed: {}", role, new_key);
+    Ok(())
+}
+
+fn process_accept_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: AuthorityRole,
+) -> ProgramResult {
+    let AuthorityTransferAccounts {
+        authority_account: new_authority_account,
+        game_account,
+        multisig_signers,
+        mut game,
+    } = AuthorityTransferAccounts::parse(program_id, accounts)?;
+    
+    // Nowy klucz musi podpisać - literówka w propozycji nie zablokuje gry
+    let pending = game.pending_authority(role)
+        .ok_or(SolanaIoError::NoPendingAuthority)?;
+    if *new_authority_account.key != pending {
+        msg!("Expected: {}, Got: {}", pending, new_authority_account.key);
+        return Err(SolanaIoError::PendingAuthorityMismatch.into());
+    }
+    check_authority_signature(program_id, new_authority_account, multisig_signers)?;
+    
+    *game.authority_mut(role) = pending;
+    *game.pending_authority_mut(role) = None;
+    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    msg!("{:?} authority updated to: {}", role, pending);
+    Ok(())
+}
+
+fn process_cancel_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: AuthorityRole,
+) -> ProgramResult {
+    let AuthorityTransferAccounts {
+        authority_account: admin_account,
+        game_account,
+        multisig_signers,
+        mut game,
+    } = AuthorityTransferAccounts::parse(program_id, accounts)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can cancel proposals");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    if game.pending_authority_mut(role).take().is_none() {
+        return Err(SolanaIoError::NoPendingAuthority.into());
+    }
+    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    msg!("{:?} authority proposal cancelled", role);
+    Ok(())
+}
+
+fn process_create_multisig(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: AuthorityRole,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> ProgramResult {
+    let CreateMultisigAccounts {
+        admin_account,
+        payer,
+        multisig_account,
+        system_program,
+        rent_account,
+        multisig_signers,
+        game,
+        multisig_bump,
+        ..
+    } = CreateMultisigAccounts::parse(program_id, accounts, role)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can create multisig");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    let multisig = Multisig::new(role, &signers, threshold, multisig_bump)?;
+    
+    let rent = Rent::from_account_info(rent_account)?;
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            multisig_account.key,
+            rent.minimum_balance(Multisig::SIZE),
+            Multisig::SIZE as u64,
+            program_id,
+        ),
+        &[
+            payer.clone(),
+            multisig_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[MULTISIG_SEED, role.seed(), &[multisig_bump]]],
+    )?;
+    
+    multisig.serialize(&mut &mut multisig_account.data.borrow_mut()[..])?;
+    
+    msg!("{:?} multisig created at {}", role, multisig_account.key);
+    Ok(())
+}
+
+fn process_set_multisig_signers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    role: AuthorityRole,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> ProgramResult {
+    let SetMultisigSignersAccounts {
+        admin_account,
+        multisig_account,
+        multisig_signers,
+        game,
+        mut multisig,
+        ..
+    } = SetMultisigSignersAccounts::parse(program_id, accounts, role)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can rotate multisig signers");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    multisig.set_signers(&signers, threshold)?;
+    multisig.serialize(&mut &mut multisig_account.data.borrow_mut()[..])?;
+    
+    msg!("{:?} multisig updated: {} of {}", role, threshold, signers.len());
+    Ok(())
+}
+
+fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    joins: bool,
+    gameplay: bool,
+    cashouts: bool,
+) -> ProgramResult {
+    let SetPausedAccounts {
+        admin_account,
+        game_account,
+        multisig_signers,
+        mut game,
+    } = SetPausedAccounts::parse(program_id, accounts)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can pause the program");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    game.paused_joins = joins;
+    game.paused_gameplay = gameplay;
+    game.paused_cashouts = cashouts;
+    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    msg!("Pause flags updated");
+    Ok(())
+}
+
+fn process_heartbeat(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let HeartbeatAccounts {
+        authority_account,
+        game_account,
+        multisig_signers,
+        mut game,
+    } = HeartbeatAccounts::parse(program_id, accounts)?;
+    
+    if *authority_account.key != game.server_authority {
+        msg!("Unauthorized: Only server authority can send heartbeat");
+        return Err(SolanaIoError::UnauthorizedServerAuthority.into());
+    }
+    check_authority_signature(program_id, authority_account, multisig_signers)?;
+    
+    game.last_heartbeat = Clock::get()?.unix_timestamp;
+    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    msg!("Heartbeat at {}", game.last_heartbeat);
+    Ok(())
+}
+
+/// Niezmiennik wypłacalności pokoju: saldo >= czynsz + pula + prowizje, a `outflow` mieści się w puli -
+/// sprawdzany przed każdym ruchem lamportów z pokoju, więc konto zostaje rent-exempt
+fn check_room_solvency(room_account: &AccountInfo, room: &Room, outflow: u64) -> ProgramResult {
+    let required = room.required_lamports(&Rent::get()?)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    if room_account.lamports() < required || outflow > room.total_pool {
+        msg!("Room {} insolvent: {} lamports, required {}, pool {}, outflow {}",
+             room.room_id, room_account.lamports(), required, room.total_pool, outflow);
+        return Err(SolanaIoError::PoolInsolvent.into());
+    }
+    Ok(())
+}
+
+/// Niezmiennik skarbca: saldo >= czynsz + prowizje do wypłaty
+fn check_fee_vault_solvency(fee_vault_account: &AccountInfo, game: &GlobalGame) -> ProgramResult {
+    let required = Rent::get()?
+        .minimum_balance(fee_vault_account.data_len())
+        .checked_add(game.platform_fee_collected)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    if fee_vault_account.lamports() < required {
+        msg!("Fee vault insolvent: {} lamports, required {}", fee_vault_account.lamports(), required);
+        return Err(SolanaIoError::PoolInsolvent.into());
+    }
+    Ok(())
+}
+
+fn process_emergency_withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let EmergencyWithdrawAccounts {
+        player_account,
+        player_state_account,
+        room_account,
+        game,
+        mut room,
+        mut player_state,
+        ..
+    } = EmergencyWithdrawAccounts::parse(program_id, accounts)?;
+    
+    if game.paused_cashouts {
+        msg!("Cash outs are paused");
+        return Err(SolanaIoError::ProgramPaused.into());
+    }
+    
+    let clock = Clock::get()?;
+    if !game.is_server_stale(clock.unix_timestamp) {
+        msg!("Server is alive (last heartbeat: {}), use CashOut", game.last_heartbeat);
+        return Err(SolanaIoError::ServerNotStale.into());
+    }
+    
+    if !player_state.is_active {
+        return Err(SolanaIoError::PlayerNotActive.into());
+    }
+    
+    if player_state.current_value == 0 {
+        return Err(SolanaIoError::NothingToCashOut.into());
+    }
+    
+    // Sesję przerwała platforma - gracz dostaje całą wartość, bez prowizji
+    let value = player_state.current_value;
+    check_room_solvency(room_account, &room, value)?;
+    **room_account.try_borrow_mut_lamports()? = room_account.lamports()
+        .checked_sub(value)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    **player_account.try_borrow_mut_lamports()? = player_account.lamports()
+        .checked_add(value)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    
+    player_state.is_active = false;
+    player_state.last_cashout = clock.unix_timestamp;
+    player_state.total_earned += value;
+    player_state.current_value = 0;
+    player_state.serialize(&mut &mut player_state_account.data.borrow_mut()[..])?;
+    
+    room.total_pool = room.total_pool.saturating_sub(value);
+    room.active_players = room.active_players.saturating_sub(1);
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    SolanaIoEvent::PlayerCashedOut {
+        player: player_state.pubkey,
+        room_id: room.room_id,
+        value,
+        payout: value,
+        fee: 0,
+        emergency: true,
+    }
+    .emit();
+    
+    msg!("Player emergency withdrew {} lamports", value);
+    Ok(())
+}
+
+/// Oddaje czynsz stanu gracza na jego portfel i zwalnia konto (wraca do system program)
+fn close_player_state(
+    player_state_account: &AccountInfo,
+    player_account: &AccountInfo,
+    player_state: &mut PlayerState,
+) -> ProgramResult {
+    if player_state.is_active {
+        msg!("Player is still active in room {}", player_state.room_id);
+        return Err(SolanaIoError::PlayerAlreadyActive.into());
+    }
+    
+    if player_state.current_value != 0 {
+        msg!("Player state still holds {} lamports", player_state.current_value);
+        return Err(SolanaIoError::PlayerStateNotEmpty.into());
+    }
+    
+    // Podbicie nonce w tej samej instrukcji co zamknięcie - bilet na ostatni nonce
+    // tego konta jest zużyty, zanim konto zniknie
+    player_state.cashout_nonce = player_state.cashout_nonce
+        .checked_add(1)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    
+    let rent_refunded = player_state_account.lamports();
+    **player_account.try_borrow_mut_lamports()? = player_account.lamports()
+        .checked_add(rent_refunded)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    **player_state_account.try_borrow_mut_lamports()? = 0;
+    player_state_account.realloc(0, false)?;
+    player_state_account.assign(&solana_program::system_program::id());
+    
+    SolanaIoEvent::PlayerStateClosed {
+        player: player_state.pubkey,
+        rent_refunded,
+    }
+    .emit();
+    
+    msg!("Player state closed, {} lamports of rent refunded", rent_refunded);
+    Ok(())
+}
+
+fn process_close_player_state(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let ClosePlayerStateAccounts {
+        player_account,
+        player_state_account,
+        mut player_state,
+    } = ClosePlayerStateAccounts::parse(program_id, accounts)?;
+    
+    close_player_state(player_state_account, player_account, &mut player_state)
+}
+
+fn process_check_solvency(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let CheckSolvencyAccounts {
+        fee_vault_account,
+        room_accounts,
+        game,
+        rooms,
+        ..
+    } = CheckSolvencyAccounts::parse(program_id, accounts)?;
+    
+    // Sprawdzamy wszystko, żeby log pokazał każde niewypłacalne konto, nie tylko pierwsze
+    let mut solvent = check_fee_vault_solvency(fee_vault_account, &game).is_ok();
+    for (room_account, room) in room_accounts.iter().zip(&rooms) {
+        solvent &= check_room_solvency(room_account, room, 0).is_ok();
+    }
+    
+    if !solvent {
+        return Err(SolanaIoError::PoolInsolvent.into());
+    }
+    msg!("Fee vault and {} rooms are solvent", rooms.len());
+    Ok(())
+}
+
+fn process_reconcile(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    room_id: u32,
+    player_count: u8,
+    start: bool,
+    finish: bool,
+) -> ProgramResult {
+    let ReconcileAccounts {
+        admin_account,
+        payer,
+        room_account,
+        reconciliation_account,
+        system_program,
+        rent_account,
+        multisig_signers,
+        game,
+        mut room,
+        player_states,
+        reconciliation,
+        reconciliation_bump,
+        ..
+    } = ReconcileAccounts::parse(program_id, accounts, room_id, player_count)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can reconcile rooms");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    // Między stronami żaden stan gracza ani licznik pokoju nie może się zmienić
+    if !(game.paused_joins && game.paused_gameplay && game.paused_cashouts) {
+        return Err(SolanaIoError::GameNotPaused.into());
+    }
+    
+    let clock = Clock::get()?;
+    let mut reconciliation = match reconciliation {
+        Some(reconciliation) => reconciliation,
+        None if start => {
+            let rent = Rent::from_account_info(rent_account)?;
+            invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    reconciliation_account.key,
+                    rent.minimum_balance(Reconciliation::SIZE),
+                    Reconciliation::SIZE as u64,
+                    program_id,
+                ),
+                &[
+                    payer.clone(),
+                    reconciliation_account.clone(),
+                    system_program.clone(),
+                ],
+                &[&[RECONCILIATION_SEED, &room_id.to_le_bytes(), &[reconciliation_bump]]],
+            )?;
+            Reconciliation {
+                is_initialized: true,
+                room_id,
+                bump: reconciliation_bump,
+                ..Reconciliation::default()
+            }
+        }
+        None => return Err(SolanaIoError::ReconciliationNotStarted.into()),
+    };
+    
+    if start {
+        reconciliation.in_progress = true;
+        reconciliation.cursor = Pubkey::default();
+        reconciliation.players_scanned = 0;
+        reconciliation.active_players = 0;
+        reconciliation.total_pool = 0;
+        reconciliation.started_at = clock.unix_timestamp;
+    } else if !reconciliation.in_progress {
+        return Err(SolanaIoError::ReconciliationNotStarted.into());
+    }
+    
+    // Rosnąca kolejność adresów gwarantuje, że żaden gracz nie zostanie policzony dwa razy
+    for player_state in &player_states {
+        if player_state.pubkey <= reconciliation.cursor {
+            msg!("Player {} is not after {}", player_state.pubkey, reconciliation.cursor);
+            return Err(SolanaIoError::ReconcilePlayersNotSorted.into());
+        }
+        reconciliation.cursor = player_state.pubkey;
+        reconciliation.players_scanned += 1;
+        if player_state.is_active {
+            reconciliation.active_players += 1;
+            reconciliation.total_pool = reconciliation.total_pool
+                .checked_add(player_state.current_value)
+                .ok_or(SolanaIoError::MathOverflow)?;
+        }
+    }
+    
+    if finish {
+        reconciliation.in_progress = false;
+        reconciliation.completed_at = clock.unix_timestamp;
+        reconciliation.previous_active_players = room.active_players;
+        reconciliation.previous_total_pool = room.total_pool;
+        room.active_players = reconciliation.active_players;
+        room.total_pool = reconciliation.total_pool;
+        
+        // Przeliczona pula nadal musi mieć pokrycie w lamportach pokoju
+        check_room_solvency(room_account, &room, 0)?;
+        room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+        
+        SolanaIoEvent::RoomReconciled {
+            room_id,
+            players_scanned: reconciliation.players_scanned,
+            previous_active_players: reconciliation.previous_active_players,
+            active_players: room.active_players,
+            previous_total_pool: reconciliation.previous_total_pool,
+            total_pool: room.total_pool,
+        }
+        .emit();
+        
+        msg!("Room {} reconciled: {} active players (was {}), pool {} (was {})",
+             room_id, room.active_players, reconciliation.previous_active_players,
+             room.total_pool, reconciliation.previous_total_pool);
+    }
+    
+    reconciliation.serialize(&mut &mut reconciliation_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn process_create_room(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    room_id: u32,
+) -> ProgramResult {
+    let CreateRoomAccounts {
+        admin_account,
+        payer,
+        room_account,
+        system_program,
+        rent_account,
+        multisig_signers,
+        game,
+        room_bump,
+        ..
+    } = CreateRoomAccounts::parse(program_id, accounts, room_id)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can create rooms");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    let rent = Rent::from_account_info(rent_account)?;
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            room_account.key,
+            rent.minimum_balance(Room::SIZE),
+            Room::SIZE as u64,
+            program_id,
+        ),
+        &[
+            payer.clone(),
+            room_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[ROOM_SEED, &room_id.to_le_bytes(), &[room_bump]]],
+    )?;
+    
+    let room = Room {
+        is_initialized: true,
+        room_id,
+        total_pool: 0,
+        active_players: 0,
+        total_players: 0,
+        min_stake: game.min_stake,
+        max_stake: game.max_stake,
+        created_at: Clock::get()?.unix_timestamp,
+        bump: room_bump,
+        fee_collected: 0,
+    };
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    msg!("Room {} created at {}", room_id, room_account.key);
+    Ok(())
+}
+
+fn process_update_room_params(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    room_id: u32,
+    min_stake: Option<u64>,
+    max_stake: Option<u64>,
+) -> ProgramResult {
+    let UpdateRoomParamsAccounts {
+        admin_account,
+        room_account,
+        multisig_signers,
+        game,
+        mut room,
+        ..
+    } = UpdateRoomParamsAccounts::parse(program_id, accounts, room_id)?;
+    
+    if *admin_account.key != game.admin_authority {
+        msg!("Unauthorized: Only admin authority can update room params");
+        return Err(SolanaIoError::UnauthorizedAdminAuthority.into());
+    }
+    check_authority_signature(program_id, admin_account, multisig_signers)?;
+    
+    if let Some(min) = min_stake {
+        room.min_stake = min;
+    }
+    
+    if let Some(max) = max_stake {
+        room.max_stake = max;
+    }
+    
+    if room.min_stake > room.max_stake {
+        msg!("Invalid stake range: min {} > max {}", room.min_stake, room.max_stake);
+        return Err(SolanaIoError::InvalidStakeRange.into());
+    }
+    
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    msg!("Room {} parameters updated", room_id);
+    Ok(())
+}
+
+fn process_collect_room_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let CollectRoomFeesAccounts {
+        room_account,
+        game_account,
+        fee_vault_account,
+        mut game,
+        mut room,
+    } = CollectRoomFeesAccounts::parse(program_id, accounts)?;
+    
+    let amount = room.fee_collected;
+    if amount == 0 {
+        msg!("Room {} has no fees to collect", room.room_id);
+        return Ok(());
+    }
+    
+    // Saldo pokoju pokrywa czynsz, pulę i prowizje - sama pula graczy zostaje nietknięta
+    check_room_solvency(room_account, &room, 0)?;
+    **room_account.try_borrow_mut_lamports()? = room_account.lamports()
+        .checked_sub(amount)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    **fee_vault_account.try_borrow_mut_lamports()? = fee_vault_account.lamports()
+        .checked_add(amount)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    
+    room.fee_collected = 0;
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    game.platform_fee_collected = game.platform_fee_collected
+        .checked_add(amount)
+        .ok_or(SolanaIoError::MathOverflow)?;
+    game.to_account_data(&mut game_account.data.borrow_mut())?;
+    
+    msg!("Collected {} lamports of fees from room {}", amount, room.room_id);
+    Ok(())
+}
+
+fn process_batch_update_player_values(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    events: Vec<EatEvent>,
+) -> ProgramResult {
+    let BatchUpdatePlayerValuesAccounts {
+        authority_account,
+        room_account,
+        player_state_accounts,
+        multisig_signers,
+        game,
+        mut room,
+        mut player_states,
+        ..
+    } = BatchUpdatePlayerValuesAccounts::parse(program_id, accounts, &events)?;
+    
+    // Te same warunki co w UpdatePlayerValue - raz dla całej paczki
+    if *authority_account.key != game.server_authority {
+        msg!("Unauthorized: Only server authority can update player values");
+        msg!("Expected: {}, Got: {}", game.server_authority, authority_account.key);
+        return Err(SolanaIoError::UnauthorizedServerAuthority.into());
+    }
+    check_authority_signature(program_id, authority_account, multisig_signers)?;
+    
+    if game.paused_gameplay {
+        msg!("Gameplay is paused");
+        return Err(SolanaIoError::ProgramPaused.into());
+    }
+    
+    if game.is_server_stale(Clock::get()?.unix_timestamp) {
+        msg!("Server heartbeat is stale (last: {})", game.last_heartbeat);
+        return Err(SolanaIoError::ServerStale.into());
+    }
+    
+    // Zdarzenia stosujemy po kolei na stanie w pamięci, więc łańcuch
+    // (A zjada B, potem C zjada A) widzi wartość A już po pierwszym zjedzeniu
+    let players = EatEvent::players(&events);
+    let index_of = |key: &Pubkey| {
+        players
+            .iter()
+            .position(|player| player == key)
+            .ok_or(SolanaIoError::InvalidPlayerStateAccount)
+    };
+    for (i, event) in events.iter().enumerate() {
+        let eater = index_of(&event.player)?;
+        let eaten = index_of(&event.eaten_player)?;
+        
+        if !player_states[eater].is_active {
+            msg!("Event {}: player {} is not active", i, event.player);
+            return Err(SolanaIoError::PlayerNotActive.into());
+        }
+        if !player_states[eaten].is_active {
+            msg!("Event {}: eaten player {} is not active", i, event.eaten_player);
+            return Err(SolanaIoError::EatenPlayerNotActive.into());
+        }
+        
+        let moved_value = player_states[eaten].current_value;
+        if event.eaten_value != moved_value {
+            msg!("Event {}: eaten value mismatch: argument {} != on-chain {}",
+                 i, event.eaten_value, moved_value);
+            return Err(SolanaIoError::EatenValueMismatch.into());
+        }
+        
+        player_states[eater].current_value = player_states[eater].current_value
+            .checked_add(moved_value)
+            .ok_or(SolanaIoError::MathOverflow)?;
+        player_states[eaten].current_value = 0;
+        player_states[eaten].is_active = false;
+        
+        SolanaIoEvent::PlayerEaten {
+            room_id: room.room_id,
+            player: event.player,
+            eaten_player: event.eaten_player,
+            eaten_value: moved_value,
+            new_value: player_states[eater].current_value,
+        }
+        .emit();
+    }
+    
+    for (state, account) in player_states.iter().zip(player_state_accounts.iter()) {
+        state.serialize(&mut &mut account.data.borrow_mut()[..])?;
+    }
+    
+    // Każde zdarzenie dezaktywuje jednego gracza - licznik pokoju raz na paczkę
+    room.active_players = room.active_players.saturating_sub(events.len() as u32);
+    room.serialize(&mut &mut room_account.data.borrow_mut()[..])?;
+    
+    msg!("Applied {} eat events in room {}", events.len(), room.room_id);
+    Ok(())
+}
@@ -0,0 +1,233 @@
+// program/src/ticket.rs
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    ed25519_program,
+    instruction::Instruction,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+
+use crate::error::SolanaIoError;
+use crate::solana_io::Multisig;
+
+// Bilety wypłat podpisane przez serwer. Podpis sprawdza precompile ed25519
+// w instrukcji tuż przed CashOut - program czyta ją z sysvara instrukcji
+// i sprawdza tylko, kto podpisał i jaką wiadomość.
+//
+// Podpisywana wiadomość: [TICKET_DOMAIN, program id (32), rodzaj biletu (1), bilet (borsh)].
+// Domena i program id nie pozwalają użyć podpisu serwera poza tym programem,
+// a rodzaj biletu - użyć biletu jednego rodzaju jako innego.
+
+/// Stały prefiks każdej wiadomości biletu
+pub const TICKET_DOMAIN: &[u8] = b"solana-io:ticket:v1";
+
+/// Rodzaj biletu - bajt za program id w podpisywanej wiadomości
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketKind {
+    CashOut = 0,
+    PartialCashOut = 1,
+}
+
+/// Wiadomość podpisywana przez serwer dla biletu `kind` o treści `body`
+pub fn ticket_message(program_id: &Pubkey, kind: TicketKind, body: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(TICKET_DOMAIN.len() + PUBKEY_SIZE + 1 + body.len());
+    message.extend_from_slice(TICKET_DOMAIN);
+    message.extend_from_slice(program_id.as_ref());
+    message.push(kind as u8);
+    message.extend_from_slice(body);
+    message
+}
+
+/// Bilet wypłaty wystawiony przez serwer gry
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CashOutTicket {
+    pub player: Pubkey, // 32 bajty - gracz, który wypłaca
+    pub amount: u64,    // 8 bajtów - wartość gracza według serwera
+    pub nonce: u64,     // 8 bajtów - musi równać się PlayerState.cashout_nonce
+    pub expiry: i64,    // 8 bajtów - timestamp, po którym bilet jest nieważny
+}
+
+impl CashOutTicket {
+    pub const KIND: TicketKind = TicketKind::CashOut;
+
+    /// Treść biletu, bez domeny i prefiksu
+    pub fn body(&self) -> Vec<u8> {
+        self.try_to_vec().expect("ticket serialization")
+    }
+
+    /// Bajty podpisywane przez serwer
+    pub fn message(&self, program_id: &Pubkey) -> Vec<u8> {
+        ticket_message(program_id, Self::KIND, &self.body())
+    }
+}
+
+/// Bilet częściowej wypłaty - serwer potwierdza bieżącą wartość gracza i kwotę,
+/// o którą zmniejszy jego masę w grze.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PartialCashOutTicket {
+    pub player: Pubkey, // 32 bajty - gracz, który wypłaca
+    pub value: u64,     // 8 bajtów - wartość gracza według serwera przed wypłatą
+    pub amount: u64,    // 8 bajtów - wypłacana część wartości (z prowizją)
+    pub nonce: u64,     // 8 bajtów - musi równać się PlayerState.cashout_nonce
+    pub expiry: i64,    // 8 bajtów - timestamp, po którym bilet jest nieważny
+}
+
+impl PartialCashOutTicket {
+    pub const KIND: TicketKind = TicketKind::PartialCashOut;
+
+    /// Treść biletu, bez domeny i prefiksu
+    pub fn body(&self) -> Vec<u8> {
+        self.try_to_vec().expect("ticket serialization")
+    }
+
+    /// Bajty podpisywane przez serwer
+    pub fn message(&self, program_id: &Pubkey) -> Vec<u8> {
+        ticket_message(program_id, Self::KIND, &self.body())
+    }
+}
+
+// Układ danych instrukcji ed25519: [liczba podpisów, padding, offsety...]
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const PUBKEY_SIZE: usize = 32;
+const SIGNATURE_SIZE: usize = 64;
+// Indeks instrukcji oznaczający "dane w tej samej instrukcji"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, ProgramError> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| SolanaIoError::InvalidCashOutTicket.into())
+}
+
+/// Pary (klucz, podpisana wiadomość) z instrukcji ed25519 tuż przed bieżącą
+pub fn ed25519_signatures(
+    instructions_sysvar: &AccountInfo,
+) -> Result<Vec<(Pubkey, Vec<u8>)>, ProgramError> {
+    let current = load_current_index_checked(instructions_sysvar)? as usize;
+    if current == 0 {
+        msg!("Cash out ticket signature instruction is missing");
+        return Err(SolanaIoError::InvalidCashOutTicket.into());
+    }
+    let instruction = load_instruction_at_checked(current - 1, instructions_sysvar)?;
+    if instruction.program_id != ed25519_program::id() {
+        msg!("Instruction before cash out is not an ed25519 verification");
+        return Err(SolanaIoError::InvalidCashOutTicket.into());
+    }
+
+    let data = &instruction.data;
+    let count = *data.first().ok_or(SolanaIoError::InvalidCashOutTicket)? as usize;
+    let mut signatures = Vec::with_capacity(count);
+    for i in 0..count {
+        let offsets = SIGNATURE_OFFSETS_START + i * SIGNATURE_OFFSETS_SIZE;
+        let signature_ix = read_u16(data, offsets + 2)?;
+        let pubkey_offset = read_u16(data, offsets + 4)? as usize;
+        let pubkey_ix = read_u16(data, offsets + 6)?;
+        let message_offset = read_u16(data, offsets + 8)? as usize;
+        let message_size = read_u16(data, offsets + 10)? as usize;
+        let message_ix = read_u16(data, offsets + 12)?;
+
+        // Dane z innych instrukcji mogłyby podmienić klucz albo wiadomość
+        if signature_ix != CURRENT_INSTRUCTION
+            || pubkey_ix != CURRENT_INSTRUCTION
+            || message_ix != CURRENT_INSTRUCTION
+        {
+            return Err(SolanaIoError::InvalidCashOutTicket.into());
+        }
+
+        let pubkey = data
+            .get(pubkey_offset..pubkey_offset + PUBKEY_SIZE)
+            .ok_or(SolanaIoError::InvalidCashOutTicket)?;
+        let signed = data
+            .get(message_offset..message_offset + message_size)
+            .ok_or(SolanaIoError::InvalidCashOutTicket)?;
+        let pubkey = Pubkey::try_from(pubkey).map_err(|_| SolanaIoError::InvalidCashOutTicket)?;
+        signatures.push((pubkey, signed.to_vec()));
+    }
+    Ok(signatures)
+}
+
+/// Czy `message` to bilet `kind` o treści `body` dla tego programu
+fn is_ticket_message(program_id: &Pubkey, kind: TicketKind, body: &[u8], message: &[u8]) -> bool {
+    let Some(rest) = message.strip_prefix(TICKET_DOMAIN) else {
+        return false;
+    };
+    let Some(rest) = rest.strip_prefix(program_id.as_ref()) else {
+        return false;
+    };
+    match rest.split_first() {
+        Some((&signed_kind, signed_body)) => signed_kind == kind as u8 && signed_body == body,
+        None => false,
+    }
+}
+
+/// Bilet `kind` o treści `body` podpisał server authority: zwykły klucz albo
+/// `threshold` członków multisig. Liczą się tylko podpisy wiadomości z domeną
+/// biletów, tym program id i tym rodzajem biletu.
+pub fn check_ticket_signers(
+    program_id: &Pubkey,
+    kind: TicketKind,
+    body: &[u8],
+    server_authority: &Pubkey,
+    server_multisig: Option<&Multisig>,
+    signatures: &[(Pubkey, Vec<u8>)],
+) -> Result<(), ProgramError> {
+    let signers: Vec<Pubkey> = signatures
+        .iter()
+        .filter(|(_, message)| is_ticket_message(program_id, kind, body, message))
+        .map(|(signer, _)| *signer)
+        .collect();
+    let signed = match server_multisig {
+        Some(multisig) => {
+            let count = multisig
+                .members()
+                .iter()
+                .filter(|member| signers.contains(member))
+                .count();
+            count >= multisig.threshold as usize
+        }
+        None => signers.contains(server_authority),
+    };
+    if !signed {
+        msg!("Cash out ticket is not signed by the server authority");
+        return Err(SolanaIoError::InvalidCashOutTicket.into());
+    }
+    Ok(())
+}
+
+/// Instrukcja precompile ed25519 z podpisami `signatures` tej samej wiadomości.
+/// Musi stać w transakcji bezpośrednio przed CashOut.
+pub fn ed25519_instruction(signatures: &[(Pubkey, [u8; SIGNATURE_SIZE])], message: &[u8]) -> Instruction {
+    let header_size = SIGNATURE_OFFSETS_START + signatures.len() * SIGNATURE_OFFSETS_SIZE;
+    let message_offset = header_size + signatures.len() * (PUBKEY_SIZE + SIGNATURE_SIZE);
+
+    let mut data = Vec::with_capacity(message_offset + message.len());
+    data.push(signatures.len() as u8);
+    data.push(0);
+    for i in 0..signatures.len() {
+        let pubkey_offset = header_size + i * (PUBKEY_SIZE + SIGNATURE_SIZE);
+        let signature_offset = pubkey_offset + PUBKEY_SIZE;
+        for value in [
+            signature_offset as u16,
+            CURRENT_INSTRUCTION,
+            pubkey_offset as u16,
+            CURRENT_INSTRUCTION,
+            message_offset as u16,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    for (pubkey, signature) in signatures {
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(signature);
+    }
+    data.extend_from_slice(message);
+
+    Instruction::new_with_bytes(ed25519_program::id(), &data, vec![])
+}
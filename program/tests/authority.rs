@@ -0,0 +1,172 @@
+// program/tests/authority.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, instruction, instruction::AuthorityRole};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn admin_rotates_server_authority() {
+    let mut env = start_initialized(2).await;
+    let admin = env.admin.insecure_clone();
+    let new_server = Keypair::new();
+    env.join(0, LAMPORTS_PER_SOL).await.unwrap();
+    env.join(1, LAMPORTS_PER_SOL).await.unwrap();
+
+    // Admin nie jest kluczem rozgrywki
+    let ix = instruction::update_player_value(
+        &env.program_id,
+        &admin.pubkey(),
+        ROOM,
+        &env.player(0),
+        &env.player(1),
+        LAMPORTS_PER_SOL,
+    );
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    // Wyciek gorącego klucza: admin proponuje nowy, stary działa do akceptacji
+    let ix = instruction::propose_authority(
+        &env.program_id,
+        &admin.pubkey(),
+        AuthorityRole::Server,
+        &new_server.pubkey(),
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+    let game = env.game().await;
+    assert_eq!(game.server_authority, env.server.pubkey());
+    assert_eq!(game.pending_server_authority, Some(new_server.pubkey()));
+
+    let ix =
+        instruction::accept_authority(&env.program_id, &new_server.pubkey(), AuthorityRole::Server);
+    env.process(&[ix], &[&new_server]).await.unwrap();
+    let game = env.game().await;
+    assert_eq!(game.server_authority, new_server.pubkey());
+    assert_eq!(game.pending_server_authority, None);
+    assert_eq!(game.admin_authority, admin.pubkey());
+
+    let result = env.eat(0, 1, LAMPORTS_PER_SOL).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    env.server = new_server;
+    env.eat(0, 1, LAMPORTS_PER_SOL).await.unwrap();
+    assert_eq!(
+        env.player_state(&env.player(0))
+            .await
+            .unwrap()
+            .current_value,
+        2 * LAMPORTS_PER_SOL
+    );
+}
+
+#[tokio::test]
+async fn admin_authority_transfer() {
+    let mut env = start_initialized(0).await;
+    let old_admin = env.admin.insecure_clone();
+    let new_admin = Keypair::new();
+
+    let ix = instruction::propose_authority(
+        &env.program_id,
+        &old_admin.pubkey(),
+        AuthorityRole::Admin,
+        &new_admin.pubkey(),
+    );
+    env.process(&[ix], &[&old_admin]).await.unwrap();
+
+    let ix =
+        instruction::accept_authority(&env.program_id, &new_admin.pubkey(), AuthorityRole::Admin);
+    env.process(&[ix], &[&new_admin]).await.unwrap();
+    assert_eq!(env.game().await.admin_authority, new_admin.pubkey());
+
+    // Stary admin traci uprawnienia, nowy je ma
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &old_admin.pubkey(),
+        None,
+        None,
+        Some(3),
+        None,
+        None,
+    );
+    let result = env.process(&[ix], &[&old_admin]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &new_admin.pubkey(),
+        None,
+        None,
+        Some(3),
+        None,
+        None,
+    );
+    env.process(&[ix], &[&new_admin]).await.unwrap();
+    assert_eq!(env.game().await.platform_fee_percent, 3);
+}
+
+#[tokio::test]
+async fn authority_transfer_rejections() {
+    let mut env = start_initialized(0).await;
+    let admin = env.admin.insecure_clone();
+    let server = env.server.insecure_clone();
+    let typo = Keypair::new();
+    let intended = Keypair::new();
+
+    // Bez propozycji nie ma czego akceptować ani anulować
+    let ix = instruction::accept_authority(&env.program_id, &typo.pubkey(), AuthorityRole::Admin);
+    let result = env.process(&[ix], &[&typo]).await;
+    assert_custom_error(result, SolanaIoError::NoPendingAuthority);
+
+    let ix = instruction::cancel_proposal(&env.program_id, &admin.pubkey(), AuthorityRole::Admin);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::NoPendingAuthority);
+
+    // Tylko admin proponuje - również nie klucz serwera
+    let ix = instruction::propose_authority(
+        &env.program_id,
+        &server.pubkey(),
+        AuthorityRole::Server,
+        &typo.pubkey(),
+    );
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    // Literówka w propozycji - nikt poza zaproponowanym kluczem nie przejmie roli
+    let ix = instruction::propose_authority(
+        &env.program_id,
+        &admin.pubkey(),
+        AuthorityRole::Admin,
+        &typo.pubkey(),
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+
+    let ix =
+        instruction::accept_authority(&env.program_id, &intended.pubkey(), AuthorityRole::Admin);
+    let result = env.process(&[ix], &[&intended]).await;
+    assert_custom_error(result, SolanaIoError::PendingAuthorityMismatch);
+
+    // Propozycja dla jednej roli nie działa dla drugiej
+    let ix = instruction::accept_authority(&env.program_id, &typo.pubkey(), AuthorityRole::Server);
+    let result = env.process(&[ix], &[&typo]).await;
+    assert_custom_error(result, SolanaIoError::NoPendingAuthority);
+
+    // Anulować może tylko admin
+    let ix = instruction::cancel_proposal(&env.program_id, &typo.pubkey(), AuthorityRole::Admin);
+    let result = env.process(&[ix], &[&typo]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    let ix = instruction::cancel_proposal(&env.program_id, &admin.pubkey(), AuthorityRole::Admin);
+    env.process(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(env.game().await.pending_admin_authority, None);
+
+    let ix = instruction::accept_authority(&env.program_id, &typo.pubkey(), AuthorityRole::Admin);
+    let result = env.process(&[ix], &[&typo]).await;
+    assert_custom_error(result, SolanaIoError::NoPendingAuthority);
+
+    let game = env.game().await;
+    assert_eq!(game.admin_authority, admin.pubkey());
+    assert_eq!(game.server_authority, server.pubkey());
+}
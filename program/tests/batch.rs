@@ -0,0 +1,117 @@
+// program/tests/batch.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, instruction, instruction::EatEvent};
+use solana_program_test::BanksClientError;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+fn eat_event(env: &TestEnv, player: usize, eaten: usize, eaten_value: u64) -> EatEvent {
+    EatEvent {
+        player: env.player(player),
+        eaten_player: env.player(eaten),
+        eaten_value,
+    }
+}
+
+async fn batch(env: &mut TestEnv, events: &[EatEvent]) -> Result<(), BanksClientError> {
+    let server = env.server.insecure_clone();
+    let ix =
+        instruction::batch_update_player_values(&env.program_id, &server.pubkey(), ROOM, events);
+    env.process(&[ix], &[&server]).await
+}
+
+#[tokio::test]
+async fn batch_applies_events_in_order() {
+    let mut env = start_initialized(4).await;
+    for i in 0..4 {
+        env.join(i, STAKE).await.unwrap();
+    }
+
+    // Łańcuch: 0 zjada 1, 2 zjada 0 (już z wartością 1), 2 zjada 3
+    let events = [
+        eat_event(&env, 0, 1, STAKE),
+        eat_event(&env, 2, 0, 2 * STAKE),
+        eat_event(&env, 2, 3, STAKE),
+    ];
+    batch(&mut env, &events).await.unwrap();
+
+    let winner = env.player_state(&env.player(2)).await.unwrap();
+    assert!(winner.is_active);
+    assert_eq!(winner.current_value, 4 * STAKE);
+    for i in [0, 1, 3] {
+        let state = env.player_state(&env.player(i)).await.unwrap();
+        assert!(!state.is_active);
+        assert_eq!(state.current_value, 0);
+    }
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 1);
+    assert_eq!(room.total_pool, 4 * STAKE);
+
+    env.cash_out(2).await.unwrap();
+}
+
+#[tokio::test]
+async fn batch_rejections() {
+    let mut env = start_initialized(4).await;
+    for i in 0..3 {
+        env.join(i, STAKE).await.unwrap();
+    }
+
+    let result = batch(&mut env, &[]).await;
+    assert_custom_error(result, SolanaIoError::EmptyEatBatch);
+
+    let events = [eat_event(&env, 0, 0, STAKE)];
+    let result = batch(&mut env, &events).await;
+    assert_custom_error(result, SolanaIoError::SelfEatNotAllowed);
+
+    // Zła wartość w drugim zdarzeniu cofa całą paczkę
+    let events = [
+        eat_event(&env, 0, 1, STAKE),
+        eat_event(&env, 0, 2, 2 * STAKE),
+    ];
+    let result = batch(&mut env, &events).await;
+    assert_custom_error(result, SolanaIoError::EatenValueMismatch);
+    assert_eq!(
+        env.player_state(&env.player(0))
+            .await
+            .unwrap()
+            .current_value,
+        STAKE
+    );
+    assert!(env.player_state(&env.player(1)).await.unwrap().is_active);
+
+    // Zjedzony gracz nie może zostać zjedzony ponownie ani jeść dalej
+    let events = [eat_event(&env, 0, 1, STAKE), eat_event(&env, 2, 1, 0)];
+    let result = batch(&mut env, &events).await;
+    assert_custom_error(result, SolanaIoError::EatenPlayerNotActive);
+    let events = [eat_event(&env, 0, 1, STAKE), eat_event(&env, 1, 2, STAKE)];
+    let result = batch(&mut env, &events).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+
+    // Gracz spoza pokoju i brakujący gracz w koncie stanu
+    env.create_room(2).await.unwrap();
+    env.join_room(3, 2, STAKE).await.unwrap();
+    let events = [eat_event(&env, 0, 3, STAKE)];
+    let result = batch(&mut env, &events).await;
+    assert_custom_error(result, SolanaIoError::PlayerRoomMismatch);
+
+    let server = env.server.insecure_clone();
+    let events = [eat_event(&env, 0, 1, STAKE)];
+    let mut ix =
+        instruction::batch_update_player_values(&env.program_id, &server.pubkey(), ROOM, &events);
+    ix.accounts[4].pubkey = env.player_state_address(&env.player(2));
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+
+    // Tylko server authority
+    let admin = env.admin.insecure_clone();
+    let ix =
+        instruction::batch_update_player_values(&env.program_id, &admin.pubkey(), ROOM, &events);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    assert_eq!(env.room(ROOM).await.active_players, 3);
+}
@@ -0,0 +1,180 @@
+// program/tests/cli.rs
+mod common;
+
+use common::*;
+use solana_io::cli::{
+    format_game, format_player, format_player_row, format_sol, parse_args, parse_sol, CliError,
+    Command, Config, DEFAULT_URL,
+};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn parses_commands_and_options() {
+    let wallet = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+
+    let (config, command) = parse_args(&args(&format!(
+        "--url http://localhost:8899 --program-id {} show-player {}",
+        program_id, wallet
+    )))
+    .unwrap();
+    assert_eq!(
+        config,
+        Config {
+            url: "http://localhost:8899".to_string(),
+            keypair_path: None,
+            program_id: Some(program_id),
+        }
+    );
+    assert_eq!(command, Command::ShowPlayer { wallet });
+
+    let (config, command) = parse_args(&args(
+        "--keypair admin.json set-params --max-stake 2.5 --fee-percent 7 --heartbeat-timeout 60",
+    ))
+    .unwrap();
+    assert_eq!(config.url, DEFAULT_URL);
+    assert_eq!(config.keypair_path.as_deref(), Some("admin.json"));
+    assert_eq!(
+        command,
+        Command::SetParams {
+            min_stake: None,
+            max_stake: Some(5 * LAMPORTS_PER_SOL / 2),
+            platform_fee_percent: Some(7),
+            platform_fee_wallet: None,
+            heartbeat_timeout: Some(60),
+        }
+    );
+
+    let (_, command) = parse_args(&args("room-params 2 --min-stake 0.5")).unwrap();
+    assert_eq!(
+        command,
+        Command::RoomParams {
+            room_id: 2,
+            min_stake: Some(LAMPORTS_PER_SOL / 2),
+            max_stake: None,
+        }
+    );
+    assert!(matches!(
+        parse_args(&args("room-params --max-stake 1")),
+        Err(CliError::Usage(_))
+    ));
+
+    let (_, command) = parse_args(&args("list-players --active --room 3")).unwrap();
+    assert_eq!(
+        command,
+        Command::ListPlayers {
+            room_id: Some(3),
+            active_only: true,
+        }
+    );
+    let (_, command) = parse_args(&args("withdraw-fees --amount 0.01")).unwrap();
+    assert_eq!(
+        command,
+        Command::WithdrawFees {
+            amount: Some(LAMPORTS_PER_SOL / 100),
+            recipient: None,
+        }
+    );
+
+    let (_, command) = parse_args(&args(&format!("force-refund {} --with-fee", wallet))).unwrap();
+    assert_eq!(
+        command,
+        Command::ForceRefund {
+            wallet,
+            charge_fee: true,
+        }
+    );
+
+    let (_, command) = parse_args(&args("reconcile 2 --page-size 10")).unwrap();
+    assert_eq!(
+        command,
+        Command::Reconcile {
+            room_id: 2,
+            page_size: 10,
+        }
+    );
+
+    assert!(matches!(
+        parse_args(&args("init --server 11111111111111111111111111111111")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(&args("show-player")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(&args("show-game extra")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(&args("--url")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(&args("deploy")),
+        Err(CliError::Usage(_))
+    ));
+    assert!(matches!(
+        parse_args(&args("reconcile 1 --page-size 0")),
+        Err(CliError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        parse_args(&args("reconcile 1 --page-size 21")),
+        Err(CliError::InvalidValue { .. })
+    ));
+    assert_eq!(
+        parse_args(&args("set-params --fee-percent 300")),
+        Err(CliError::InvalidValue {
+            flag: "--fee-percent".to_string(),
+            value: "300".to_string(),
+        })
+    );
+}
+
+#[test]
+fn sol_amounts() {
+    assert_eq!(parse_sol("1"), Some(LAMPORTS_PER_SOL));
+    assert_eq!(parse_sol("0.1"), Some(STAKE));
+    assert_eq!(parse_sol(".5"), Some(LAMPORTS_PER_SOL / 2));
+    assert_eq!(parse_sol("0.000000001"), Some(1));
+    assert_eq!(parse_sol("0.0000000001"), None);
+    assert_eq!(parse_sol("1e3"), None);
+    assert_eq!(parse_sol("-1"), None);
+    assert_eq!(parse_sol("."), None);
+    assert_eq!(parse_sol("99999999999"), None);
+
+    assert_eq!(format_sol(0), "0 SOL");
+    assert_eq!(format_sol(STAKE), "0.1 SOL");
+    assert_eq!(format_sol(3 * LAMPORTS_PER_SOL + 1), "3.000000001 SOL");
+}
+
+#[tokio::test]
+async fn formats_program_accounts() {
+    let mut env = start_initialized(1).await;
+    env.join(0, STAKE).await.unwrap();
+    let player = env.player(0);
+
+    let game = env.game().await;
+    let vault = env.lamports(&env.fee_vault_address()).await;
+    let output = format_game(&game, vault);
+    assert!(output.contains(&format!("server authority:      {}", game.server_authority)));
+    assert!(output.contains(&format!("platform fee wallet:   {}", PLATFORM_WALLET)));
+    assert!(output.contains("pending server:        -"));
+    assert!(output.contains("paused:                joins=false gameplay=false cashouts=false"));
+
+    let state = env.player_state(&player).await.unwrap();
+    let output = format_player(&state);
+    assert!(output.contains(&format!("wallet:          {}", player)));
+    assert!(output.contains(&format!("room:            {}", ROOM)));
+    assert!(output.contains("current value:   0.1 SOL"));
+    assert_eq!(
+        format_player_row(&state),
+        format!("{} room={} active=true value=0.1 SOL", player, ROOM)
+    );
+}
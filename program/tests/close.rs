@@ -0,0 +1,104 @@
+// program/tests/close.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, events::SolanaIoEvent, instruction, solana_io::PlayerState};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn close_player_state_refunds_rent() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    let state_address = env.player_state_address(&player.pubkey());
+    let rent = env.rent_minimum(PlayerState::SIZE).await;
+    env.join(0, STAKE).await.unwrap();
+
+    // Aktywny gracz nie może zamknąć stanu
+    let ixs = [instruction::close_player_state(
+        &env.program_id,
+        &player.pubkey(),
+    )];
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::PlayerAlreadyActive);
+
+    // Zamknięcie zaraz po wypłacie, w tym samym slocie
+    env.cash_out(0).await.unwrap();
+    let before = env.lamports(&player.pubkey()).await;
+    let events = env.process_events(&ixs, &[&player]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerStateClosed {
+            player: player.pubkey(),
+            rent_refunded: rent,
+        }]
+    );
+    // Opłatę transakcji płaci payer kontekstu, więc gracz dostaje cały czynsz
+    assert_eq!(env.lamports(&player.pubkey()).await, before + rent);
+    assert!(env.player_state(&player.pubkey()).await.is_none());
+    assert_eq!(env.lamports(&state_address).await, 0);
+
+    // Po zamknięciu gracz może dołączyć od nowa - konto powstaje jeszcze raz
+    env.join(0, STAKE).await.unwrap();
+    let state = env.player_state(&player.pubkey()).await.unwrap();
+    assert!(state.is_active);
+    assert_eq!(state.stake_amount, STAKE);
+    assert_eq!(env.room(ROOM).await.active_players, 1);
+}
+
+#[tokio::test]
+async fn cash_out_can_close_player_state() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    let server = env.server.insecure_clone();
+    let rent = env.rent_minimum(PlayerState::SIZE).await;
+    // Wypłata z zamknięciem w slocie dołączenia
+    env.join(0, STAKE).await.unwrap();
+
+    let ticket = env.cash_out_ticket(0).await;
+    let mut ixs = ticket_instructions(&env.program_id, &ticket, &[&server]);
+    ixs[1] = instruction::cash_out(&env.program_id, &player.pubkey(), ROOM, &ticket, true);
+    let before = env.lamports(&player.pubkey()).await;
+    let events = env.process_events(&ixs, &[&player]).await;
+    let fee = STAKE * env.game().await.platform_fee_percent as u64 / 100;
+    assert_eq!(
+        events.last(),
+        Some(&SolanaIoEvent::PlayerStateClosed {
+            player: player.pubkey(),
+            rent_refunded: rent,
+        })
+    );
+    assert_eq!(
+        env.lamports(&player.pubkey()).await,
+        before + STAKE - fee + rent
+    );
+    assert!(env.player_state(&player.pubkey()).await.is_none());
+
+    // Bilet sprzed zamknięcia nie pasuje do nowego konta, nawet na tę samą kwotę
+    // i w tym samym slocie
+    env.join(0, STAKE).await.unwrap();
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidTicketNonce);
+    env.cash_out(0).await.unwrap();
+}
+
+#[tokio::test]
+async fn close_player_state_rejections() {
+    let mut env = start_initialized(2).await;
+    let player = env.players[0].insecure_clone();
+    let other = env.players[1].insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+    env.cash_out(0).await.unwrap();
+
+    // Cudzy stan gracza
+    let mut ix = instruction::close_player_state(&env.program_id, &other.pubkey());
+    ix.accounts[1].pubkey = env.player_state_address(&player.pubkey());
+    let result = env.process(&[ix], &[&other]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+
+    // Gracz, który nigdy nie dołączył, nie ma czego zamykać
+    let ix = instruction::close_player_state(&env.program_id, &other.pubkey());
+    assert!(env.process(&[ix], &[&other]).await.is_err());
+    assert!(env.player_state(&player.pubkey()).await.is_some());
+}
@@ -0,0 +1,436 @@
+// program/tests/common/mod.rs
+#![allow(dead_code)]
+
+mod log_data;
+
+use borsh::BorshDeserialize;
+use solana_io::{
+    error::SolanaIoError,
+    events::{parse_logs, SolanaIoEvent},
+    instruction,
+    pda::{find_fee_vault_address, find_global_game_address, find_player_state_address},
+    solana_io::{AuthorityRole, GlobalGame, Multisig, PlayerState, Room},
+    ticket::{ed25519_instruction, CashOutTicket, PartialCashOutTicket},
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+/// Portfel prowizji ustawiany przy InitializeGame w testach
+pub const PLATFORM_WALLET: Pubkey = pubkey!("FEEfBE29dqRgC8qMv6f9YXTSNbX7LMN3Reo3UsYdoUd8");
+
+/// Pokój tworzony przez `start_initialized`
+pub const ROOM: u32 = 1;
+
+pub const PLAYER_STARTING_BALANCE: u64 = 20 * LAMPORTS_PER_SOL;
+pub const FEE_WALLET_STARTING_BALANCE: u64 = LAMPORTS_PER_SOL;
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+    pub server: Keypair,
+    pub admin: Keypair,
+    pub players: Vec<Keypair>,
+}
+
+/// Startuje bank z programem, portfelem prowizji i `player_count` zasilonymi graczami
+pub async fn start(player_count: usize) -> TestEnv {
+    let program_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "solana_io",
+        program_id,
+        processor!(solana_io::process_instruction),
+    );
+
+    program_test.add_account(
+        PLATFORM_WALLET,
+        Account::new(FEE_WALLET_STARTING_BALANCE, 0, &system_program::id()),
+    );
+
+    let server = Keypair::new();
+    let admin = Keypair::new();
+    let players: Vec<Keypair> = (0..player_count).map(|_| Keypair::new()).collect();
+    for player in &players {
+        program_test.add_account(
+            player.pubkey(),
+            Account::new(PLAYER_STARTING_BALANCE, 0, &system_program::id()),
+        );
+    }
+
+    let context = program_test.start_with_context().await;
+    log_data::install();
+    TestEnv {
+        context,
+        program_id,
+        server,
+        admin,
+        players,
+    }
+}
+
+/// Startuje bank, inicjalizuje grę z `server` i `admin` jako authority i tworzy pokój `ROOM`
+pub async fn start_initialized(player_count: usize) -> TestEnv {
+    let mut env = start(player_count).await;
+    let ix = instruction::initialize_game(
+        &env.program_id,
+        &env.context.payer.pubkey(),
+        &env.server.pubkey(),
+        &env.admin.pubkey(),
+        &PLATFORM_WALLET,
+    );
+    env.process(&[ix], &[]).await.unwrap();
+    env.create_room(ROOM).await.unwrap();
+    env
+}
+
+impl TestEnv {
+    /// Wysyła transakcję opłaconą przez payera, podpisaną dodatkowo przez `signers`
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await;
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Jak `process`, ale transakcja musi się udać - zwraca zdarzenia programu z logów
+    pub async fn process_events(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Vec<SolanaIoEvent> {
+        let transaction = self.transaction(instructions, signers).await;
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        result.result.unwrap();
+        let logs: Vec<String> = result
+            .metadata
+            .unwrap()
+            .log_messages
+            .into_iter()
+            .map(|line| match line.strip_prefix(log_data::LOGGED_DATA_PREFIX) {
+                Some(data) if data.starts_with("Program data: ") => data.to_string(),
+                _ => line,
+            })
+            .collect();
+        parse_logs(&self.program_id, &logs)
+    }
+
+    async fn transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Transaction {
+        // Świeży blockhash, żeby identyczne transakcje nie były odrzucane jako duplikaty
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers: Vec<&Keypair> = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        )
+    }
+
+    pub fn player(&self, index: usize) -> Pubkey {
+        self.players[index].pubkey()
+    }
+
+    pub fn game_address(&self) -> Pubkey {
+        find_global_game_address(&self.program_id).0
+    }
+
+    pub fn room_address(&self, room_id: u32) -> Pubkey {
+        instruction::room_address(&self.program_id, room_id)
+    }
+
+    pub fn player_state_address(&self, player: &Pubkey) -> Pubkey {
+        find_player_state_address(&self.program_id, player).0
+    }
+
+    pub fn fee_vault_address(&self) -> Pubkey {
+        find_fee_vault_address(&self.program_id).0
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn account(&mut self, address: &Pubkey) -> Account {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .expect("account")
+    }
+
+    /// Nadpisuje konto w banku (do podrabiania kont w testach)
+    pub fn set_account(&mut self, address: &Pubkey, account: Account) {
+        self.context
+            .set_account(address, &AccountSharedData::from(account));
+    }
+
+    pub async fn game(&mut self) -> GlobalGame {
+        let address = self.game_address();
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .expect("game account");
+        GlobalGame::from_account_data(&account.data).unwrap()
+    }
+
+    pub async fn player_state(&mut self, player: &Pubkey) -> Option<PlayerState> {
+        let address = self.player_state_address(player);
+        self.context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .map(|account| PlayerState::try_from_slice(&account.data).unwrap())
+    }
+
+    pub async fn room(&mut self, room_id: u32) -> Room {
+        let address = self.room_address(room_id);
+        let account = self.account(&address).await;
+        Room::try_from_slice(&account.data).unwrap()
+    }
+
+    pub async fn multisig(&mut self, role: AuthorityRole) -> Multisig {
+        let address = instruction::multisig_address(&self.program_id, role);
+        let account = self.account(&address).await;
+        Multisig::try_from_slice(&account.data).unwrap()
+    }
+
+    /// Przesuwa zegar banku o `seconds` sekund
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn rent_minimum(&mut self, space: usize) -> u64 {
+        self.context
+            .banks_client
+            .get_rent()
+            .await
+            .unwrap()
+            .minimum_balance(space)
+    }
+
+    pub async fn create_room(&mut self, room_id: u32) -> Result<(), BanksClientError> {
+        let ix = instruction::create_room(
+            &self.program_id,
+            &self.admin.pubkey(),
+            &self.context.payer.pubkey(),
+            room_id,
+        );
+        let admin = self.admin.insecure_clone();
+        self.process(&[ix], &[&admin]).await
+    }
+
+    pub async fn join(&mut self, index: usize, stake_amount: u64) -> Result<(), BanksClientError> {
+        self.join_room(index, ROOM, stake_amount).await
+    }
+
+    pub async fn join_room(
+        &mut self,
+        index: usize,
+        room_id: u32,
+        stake_amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix =
+            instruction::join_game(&self.program_id, &self.player(index), room_id, stake_amount);
+        let player = self.players[index].insecure_clone();
+        self.process(&[ix], &[&player]).await
+    }
+
+    pub async fn eat(
+        &mut self,
+        eater: usize,
+        eaten: usize,
+        eaten_value: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = instruction::update_player_value(
+            &self.program_id,
+            &self.server.pubkey(),
+            ROOM,
+            &self.player(eater),
+            &self.player(eaten),
+            eaten_value,
+        );
+        let server = self.server.insecure_clone();
+        self.process(&[ix], &[&server]).await
+    }
+
+    /// Bilet na pełną wartość gracza z bieżącym nonce, ważny przez minutę
+    pub async fn cash_out_ticket(&mut self, index: usize) -> CashOutTicket {
+        let player = self.player(index);
+        let state = self.player_state(&player).await.unwrap();
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        CashOutTicket {
+            player,
+            amount: state.current_value,
+            nonce: state.cashout_nonce,
+            expiry: clock.unix_timestamp + 60,
+        }
+    }
+
+    /// Bilet częściowej wypłaty `amount` z bieżącą wartością i nonce, ważny przez minutę
+    pub async fn partial_cash_out_ticket(
+        &mut self,
+        index: usize,
+        amount: u64,
+    ) -> PartialCashOutTicket {
+        let ticket = self.cash_out_ticket(index).await;
+        PartialCashOutTicket {
+            player: ticket.player,
+            value: ticket.amount,
+            amount,
+            nonce: ticket.nonce,
+            expiry: ticket.expiry,
+        }
+    }
+
+    /// Instrukcja ed25519 z podpisem serwera + CashOut
+    pub async fn cash_out_instructions(&mut self, index: usize) -> Vec<Instruction> {
+        let ticket = self.cash_out_ticket(index).await;
+        ticket_instructions(&self.program_id, &ticket, &[&self.server])
+    }
+
+    pub async fn cash_out(&mut self, index: usize) -> Result<(), BanksClientError> {
+        let ixs = self.cash_out_instructions(index).await;
+        let player = self.players[index].insecure_clone();
+        self.process(&ixs, &[&player]).await
+    }
+
+    pub async fn force_cleanup(&mut self, index: usize) -> Result<(), BanksClientError> {
+        let ix = instruction::force_cleanup(
+            &self.program_id,
+            &self.server.pubkey(),
+            ROOM,
+            &self.player(index),
+        );
+        let server = self.server.insecure_clone();
+        self.process(&[ix], &[&server]).await
+    }
+
+    pub async fn force_refund(
+        &mut self,
+        index: usize,
+        charge_fee: bool,
+    ) -> Result<(), BanksClientError> {
+        let ix = instruction::force_refund(
+            &self.program_id,
+            &self.server.pubkey(),
+            ROOM,
+            &self.player(index),
+            charge_fee,
+        );
+        let server = self.server.insecure_clone();
+        self.process(&[ix], &[&server]).await
+    }
+
+    pub async fn heartbeat(&mut self) -> Result<(), BanksClientError> {
+        let ix = instruction::heartbeat(&self.program_id, &self.server.pubkey());
+        let server = self.server.insecure_clone();
+        self.process(&[ix], &[&server]).await
+    }
+
+    pub async fn withdraw_fees(
+        &mut self,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let ix = instruction::withdraw_fees(
+            &self.program_id,
+            &self.admin.pubkey(),
+            recipient,
+            amount,
+        );
+        let admin = self.admin.insecure_clone();
+        self.process(&[ix], &[&admin]).await
+    }
+
+    pub async fn collect_room_fees(&mut self, room_id: u32) -> Result<(), BanksClientError> {
+        let ix = instruction::collect_room_fees(&self.program_id, room_id);
+        self.process(&[ix], &[]).await
+    }
+}
+
+/// Instrukcja ed25519 z podpisami `message` kluczami `signers`
+pub fn signature_instruction(message: &[u8], signers: &[&Keypair]) -> Instruction {
+    let signatures: Vec<_> = signers
+        .iter()
+        .map(|signer| {
+            let signature: [u8; 64] = signer.sign_message(message).into();
+            (signer.pubkey(), signature)
+        })
+        .collect();
+    ed25519_instruction(&signatures, message)
+}
+
+/// Podpisuje bilet kluczami `signers` i składa instrukcje ed25519 + CashOut w pokoju `ROOM`
+pub fn ticket_instructions(
+    program_id: &Pubkey,
+    ticket: &CashOutTicket,
+    signers: &[&Keypair],
+) -> Vec<Instruction> {
+    vec![
+        signature_instruction(&ticket.message(program_id), signers),
+        instruction::cash_out(program_id, &ticket.player, ROOM, ticket, false),
+    ]
+}
+
+/// Jak `ticket_instructions`, dla PartialCashOut
+pub fn partial_ticket_instructions(
+    program_id: &Pubkey,
+    ticket: &PartialCashOutTicket,
+    signers: &[&Keypair],
+) -> Vec<Instruction> {
+    vec![
+        signature_instruction(&ticket.message(program_id), signers),
+        instruction::partial_cash_out(program_id, &ticket.player, ROOM, ticket),
+    ]
+}
+
+/// Sprawdza, że transakcja padła z konkretnym błędem programu
+pub fn assert_custom_error(result: Result<(), BanksClientError>, expected: SolanaIoError) {
+    assert_instruction_error(result, InstructionError::Custom(expected as u32));
+}
+
+/// Sprawdza, że transakcja padła z konkretnym InstructionError
+pub fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(_, err))) => {
+            assert_eq!(err, expected)
+        }
+        Err(BanksClientError::SimulationError {
+            err: TransactionError::InstructionError(_, err),
+            ..
+        }) => assert_eq!(err, expected),
+        other => panic!("expected {:?}, got {:?}", expected, other),
+    }
+}
@@ -0,0 +1,215 @@
+// program/tests/events.rs
+mod common;
+
+use common::*;
+use solana_io::{
+    events::{decode_log_line, parse_logs, SolanaIoEvent},
+    instruction::{self, EatEvent},
+};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn processors_emit_events() {
+    let mut env = start(4).await;
+    let server = env.server.insecure_clone();
+    let admin = env.admin.insecure_clone();
+
+    let ix = instruction::initialize_game(
+        &env.program_id,
+        &env.context.payer.pubkey(),
+        &server.pubkey(),
+        &admin.pubkey(),
+        &PLATFORM_WALLET,
+    );
+    let events = env.process_events(&[ix], &[]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::GameInitialized {
+            server_authority: server.pubkey(),
+            admin_authority: admin.pubkey(),
+            platform_fee_wallet: PLATFORM_WALLET,
+        }]
+    );
+    env.create_room(ROOM).await.unwrap();
+
+    let player = env.players[0].insecure_clone();
+    let ix = instruction::join_game(&env.program_id, &player.pubkey(), ROOM, STAKE);
+    let events = env.process_events(&[ix], &[&player]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerJoined {
+            player: player.pubkey(),
+            room_id: ROOM,
+            stake_amount: STAKE,
+            current_value: STAKE,
+            rejoined: false,
+        }]
+    );
+    for i in 1..4 {
+        env.join(i, STAKE).await.unwrap();
+    }
+
+    let ix = instruction::update_player_value(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &env.player(0),
+        &env.player(1),
+        STAKE,
+    );
+    let events = env.process_events(&[ix], &[&server]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerEaten {
+            room_id: ROOM,
+            player: env.player(0),
+            eaten_player: env.player(1),
+            eaten_value: STAKE,
+            new_value: 2 * STAKE,
+        }]
+    );
+
+    // Paczka emituje jedno zdarzenie na zjedzenie
+    let batch = [
+        EatEvent {
+            player: env.player(2),
+            eaten_player: env.player(0),
+            eaten_value: 2 * STAKE,
+        },
+        EatEvent {
+            player: env.player(3),
+            eaten_player: env.player(2),
+            eaten_value: 3 * STAKE,
+        },
+    ];
+    let ix =
+        instruction::batch_update_player_values(&env.program_id, &server.pubkey(), ROOM, &batch);
+    let events = env.process_events(&[ix], &[&server]).await;
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[1],
+        SolanaIoEvent::PlayerEaten {
+            room_id: ROOM,
+            player: env.player(3),
+            eaten_player: env.player(2),
+            eaten_value: 3 * STAKE,
+            new_value: 4 * STAKE,
+        }
+    );
+
+    let winner = env.players[3].insecure_clone();
+    let ixs = env.cash_out_instructions(3).await;
+    let events = env.process_events(&ixs, &[&winner]).await;
+    let fee = 4 * STAKE * env.game().await.platform_fee_percent as u64 / 100;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerCashedOut {
+            player: winner.pubkey(),
+            room_id: ROOM,
+            value: 4 * STAKE,
+            payout: 4 * STAKE - fee,
+            fee,
+            emergency: false,
+        }]
+    );
+
+    env.join(1, STAKE).await.unwrap();
+    let ix = instruction::force_cleanup(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &env.player(1),
+    );
+    let events = env.process_events(&[ix], &[&server]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerForceCleaned {
+            player: env.player(1),
+            room_id: ROOM,
+            lost_value: STAKE,
+        }]
+    );
+
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        None,
+        None,
+        Some(3),
+        None,
+        None,
+    );
+    let events = env.process_events(&[ix], &[&admin]).await;
+    let game = env.game().await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::ParamsUpdated {
+            min_stake: game.min_stake,
+            max_stake: game.max_stake,
+            platform_fee_percent: 3,
+            platform_fee_wallet: PLATFORM_WALLET,
+            heartbeat_timeout: game.heartbeat_timeout,
+        }]
+    );
+
+    env.join(3, STAKE).await.unwrap();
+    env.advance_clock(game.heartbeat_timeout + 1).await;
+    let ix = instruction::emergency_withdraw(&env.program_id, &winner.pubkey(), ROOM);
+    let events = env.process_events(&[ix], &[&winner]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerCashedOut {
+            player: winner.pubkey(),
+            room_id: ROOM,
+            value: STAKE,
+            payout: STAKE,
+            fee: 0,
+            emergency: true,
+        }]
+    );
+}
+
+#[test]
+fn parse_logs_keeps_only_program_events() {
+    let program_id = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let event = SolanaIoEvent::PlayerForceCleaned {
+        player: Pubkey::new_unique(),
+        room_id: ROOM,
+        lost_value: STAKE,
+    };
+    // Ucięte zdarzenie i śmieci nie są dekodowane
+    assert!(decode_log_line("Program data: AA==").is_none());
+    assert!(decode_log_line("Program data: ???").is_none());
+
+    // Te same bajty, które `sol_log_data` zapisuje w logu jako base64
+    let data = borsh::BorshSerialize::try_to_vec(&event).unwrap();
+    let line = format!("Program data: {}", base64_encode(&data));
+    assert_eq!(decode_log_line(&line), Some(event.clone()));
+
+    let logs = vec![
+        format!("Program {} invoke [1]", other),
+        line.clone(),
+        format!("Program {} invoke [2]", program_id),
+        "Program log: Instruction: ForceCleanup".to_string(),
+        line.clone(),
+        format!(
+            "Program {} consumed 1000 of 200000 compute units",
+            program_id
+        ),
+        format!("Program {} success", program_id),
+        line.clone(),
+        format!("Program {} success", other),
+        format!("Program {} invoke [1]", program_id),
+        line.clone(),
+        format!("Program {} success", program_id),
+    ];
+    assert_eq!(parse_logs(&program_id, &logs), vec![event.clone(), event]);
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
@@ -0,0 +1,164 @@
+// program/tests/heartbeat.rs
+mod common;
+
+use common::*;
+use solana_io::{
+    error::SolanaIoError,
+    instruction,
+    solana_io::{GlobalGame, Room},
+};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+const TIMEOUT: i64 = GlobalGame::DEFAULT_HEARTBEAT_TIMEOUT;
+// Interwał heartbeatu serwera (HEARTBEAT_INTERVAL_MS w server/index.js)
+const SERVER_HEARTBEAT_INTERVAL: i64 = 60;
+
+async fn emergency_withdraw(
+    env: &mut TestEnv,
+    index: usize,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let ix = instruction::emergency_withdraw(&env.program_id, &env.player(index), ROOM);
+    let player = env.players[index].insecure_clone();
+    env.process(&[ix], &[&player]).await
+}
+
+#[tokio::test]
+async fn emergency_withdraw_after_server_goes_silent() {
+    let mut env = start_initialized(3).await;
+    let room_address = env.room_address(ROOM);
+    let room_rent = env.rent_minimum(Room::SIZE).await;
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+    env.join(2, STAKE).await.unwrap();
+    env.eat(0, 1, STAKE).await.unwrap();
+
+    // Serwer żyje - tylko zwykły CashOut
+    let result = emergency_withdraw(&mut env, 0).await;
+    assert_custom_error(result, SolanaIoError::ServerNotStale);
+
+    env.advance_clock(TIMEOUT - 10).await;
+    env.heartbeat().await.unwrap();
+    env.advance_clock(TIMEOUT - 10).await;
+    let result = emergency_withdraw(&mut env, 0).await;
+    assert_custom_error(result, SolanaIoError::ServerNotStale);
+
+    // Serwer milczy dłużej niż timeout
+    env.advance_clock(20).await;
+
+    // Zrestartowany serwer nie może działać na utraconym stanie
+    let result = env.eat(0, 2, STAKE).await;
+    assert_custom_error(result, SolanaIoError::ServerStale);
+    let result = env.force_cleanup(2).await;
+    assert_custom_error(result, SolanaIoError::ServerStale);
+
+    // Wypłata bez prowizji
+    let balance = env.lamports(&env.player(0)).await;
+    emergency_withdraw(&mut env, 0).await.unwrap();
+    assert_eq!(env.lamports(&env.player(0)).await, balance + 2 * STAKE);
+    assert_eq!(
+        env.lamports(&env.fee_vault_address()).await,
+        env.rent_minimum(0).await
+    );
+
+    let state = env.player_state(&env.player(0)).await.unwrap();
+    assert!(!state.is_active);
+    assert_eq!(state.current_value, 0);
+    assert_eq!(state.total_earned, 2 * STAKE);
+
+    let result = emergency_withdraw(&mut env, 0).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+    let result = emergency_withdraw(&mut env, 1).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.total_pool, STAKE);
+    assert_eq!(room.active_players, 1);
+    assert_eq!(env.game().await.platform_fee_collected, 0);
+    assert_eq!(env.lamports(&room_address).await, room_rent + STAKE);
+
+    // Heartbeat przywraca rozgrywkę i wyłącza EmergencyWithdraw
+    env.heartbeat().await.unwrap();
+    let result = emergency_withdraw(&mut env, 2).await;
+    assert_custom_error(result, SolanaIoError::ServerNotStale);
+    env.force_cleanup(2).await.unwrap();
+}
+
+#[tokio::test]
+async fn live_server_keeps_emergency_withdraw_closed() {
+    let mut env = start_initialized(1).await;
+    env.join(0, STAKE).await.unwrap();
+
+    // Serwer wysyła heartbeat co minutę - znacznie dłużej niż timeout łącznie
+    for _ in 0..3 * TIMEOUT / SERVER_HEARTBEAT_INTERVAL {
+        env.advance_clock(SERVER_HEARTBEAT_INTERVAL).await;
+        env.heartbeat().await.unwrap();
+        let result = emergency_withdraw(&mut env, 0).await;
+        assert_custom_error(result, SolanaIoError::ServerNotStale);
+    }
+
+    // Jeden pominięty heartbeat nie otwiera EmergencyWithdraw
+    env.advance_clock(2 * SERVER_HEARTBEAT_INTERVAL).await;
+    let result = emergency_withdraw(&mut env, 0).await;
+    assert_custom_error(result, SolanaIoError::ServerNotStale);
+    assert!(env.player_state(&env.player(0)).await.unwrap().is_active);
+}
+
+#[tokio::test]
+async fn heartbeat_timeout_is_configurable() {
+    let mut env = start_initialized(1).await;
+    let admin = env.admin.insecure_clone();
+    let server = env.server.insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        None,
+        None,
+        None,
+        None,
+        Some(0),
+    );
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::InvalidHeartbeatTimeout);
+
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        None,
+        None,
+        None,
+        None,
+        Some(30),
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(env.game().await.heartbeat_timeout, 30);
+
+    env.advance_clock(31).await;
+    emergency_withdraw(&mut env, 0).await.unwrap();
+
+    // Heartbeat tylko od server authority
+    let ix = instruction::heartbeat(&env.program_id, &admin.pubkey());
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    let before = env.game().await.last_heartbeat;
+    let ix = instruction::heartbeat(&env.program_id, &server.pubkey());
+    env.process(&[ix], &[&server]).await.unwrap();
+    assert!(env.game().await.last_heartbeat >= before + 31);
+}
+
+#[tokio::test]
+async fn emergency_withdraw_respects_cashout_pause() {
+    let mut env = start_initialized(1).await;
+    let admin = env.admin.insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+    env.advance_clock(TIMEOUT + 1).await;
+
+    let ix = instruction::set_paused(&env.program_id, &admin.pubkey(), false, false, true);
+    env.process(&[ix], &[&admin]).await.unwrap();
+    let result = emergency_withdraw(&mut env, 0).await;
+    assert_custom_error(result, SolanaIoError::ProgramPaused);
+    assert!(env.player_state(&env.player(0)).await.unwrap().is_active);
+}
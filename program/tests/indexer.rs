@@ -0,0 +1,113 @@
+// program/tests/indexer.rs
+use solana_io::indexer::{read_log_file, write_log_file, LogFileError, Projection, SessionEnd};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey, pubkey::Pubkey, system_program};
+
+// Stałe z nagrania w tests/fixtures/session.log
+const PROGRAM_ID: Pubkey = pubkey!("1111111QLbz7JHiBTspS962RLKV8GndWFwiEaqKM");
+const PLAYERS: [Pubkey; 3] = [
+    pubkey!("4qGXyYFmLBPp4Ps3bbFusSPwZ9z8waEPPReUkVpgN5vX"),
+    pubkey!("2r1HPvaZ3FxrFTLEWiz31agu4BpaEDWM62z9HRHQwQVY"),
+    pubkey!("EPYt59yXz83J6QxFd2ZAhBKhxAKV1fiLqMSMkSWE6gSD"),
+];
+const FIXTURE: &str = include_str!("fixtures/session.log");
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+const DAY1: i64 = 1_700_006_400 / 86_400;
+
+#[test]
+fn projection_from_recorded_logs() {
+    let transactions = read_log_file(FIXTURE).unwrap();
+    assert_eq!(transactions.len(), 14);
+
+    let mut projection = Projection::new();
+    assert_eq!(projection.apply_all(&PROGRAM_ID, &transactions), 14);
+    // Ponowne podanie tych samych transakcji niczego nie zmienia
+    assert_eq!(projection.apply_all(&PROGRAM_ID, &transactions), 0);
+    // Linie "Program data" są przypisywane do programu, który je zapisał
+    assert_eq!(
+        Projection::new().apply_all(&system_program::id(), &transactions),
+        0
+    );
+
+    let game = projection.game.as_ref().unwrap();
+    assert_eq!(game.platform_fee_percent, Some(10));
+
+    // Dzień 1: 3 * STAKE z prowizją 5%, dzień 2: 4 * STAKE z prowizją 10%
+    let day1_fee = 3 * STAKE / 20;
+    let day2_fee = 4 * STAKE / 10;
+    assert_eq!(
+        projection.fees_by_day.iter().collect::<Vec<_>>(),
+        vec![(&DAY1, &day1_fee), (&(DAY1 + 1), &day2_fee)]
+    );
+    assert_eq!(projection.total_fees, day1_fee + day2_fee);
+
+    let stats = |i: usize| projection.players[&PLAYERS[i]].clone();
+    assert_eq!(stats(0).sessions, 2);
+    assert_eq!(stats(0).deaths, 2);
+    assert_eq!(stats(0).pnl(), -3 * STAKE as i128);
+    assert_eq!(stats(1).kills, 1);
+    assert_eq!(stats(1).fees_paid, day2_fee);
+    assert_eq!(
+        stats(1).pnl(),
+        (4 * STAKE - day2_fee) as i128 - 3 * STAKE as i128
+    );
+    assert_eq!(stats(2).force_cleanups, 1);
+    assert_eq!(
+        stats(2).pnl(),
+        (3 * STAKE - day1_fee) as i128 - 2 * STAKE as i128
+    );
+
+    assert_eq!(projection.kills.len(), 3);
+    let kill = &projection.kills[1];
+    assert_eq!((kill.player, kill.eaten_player), (PLAYERS[2], PLAYERS[0]));
+    assert_eq!(kill.eaten_value, 2 * STAKE);
+
+    assert_eq!(projection.sessions.len(), 6);
+    assert!(projection.sessions.iter().all(|s| s.end.is_some()));
+    assert!(projection.open_session(&PLAYERS[2]).is_none());
+    assert_eq!(
+        projection.sessions[0].end,
+        Some(SessionEnd::Eaten {
+            by: PLAYERS[2],
+            value: 2 * STAKE,
+        })
+    );
+    assert_eq!(projection.sessions[2].kills, 1);
+    assert_eq!(
+        projection.sessions[2].end,
+        Some(SessionEnd::CashedOut {
+            value: 3 * STAKE,
+            payout: 3 * STAKE - day1_fee,
+            fee: day1_fee,
+        })
+    );
+    assert_eq!(
+        projection.sessions[5].end,
+        Some(SessionEnd::ForceCleaned { lost_value: STAKE })
+    );
+}
+
+#[test]
+fn log_file_format() {
+    let transactions = read_log_file(FIXTURE).unwrap();
+    assert_eq!(
+        read_log_file(&write_log_file(&transactions)),
+        Ok(transactions)
+    );
+
+    let transactions = read_log_file("tx abc 7 -\nProgram log: x\n").unwrap();
+    assert_eq!(transactions[0].slot, 7);
+    assert_eq!(transactions[0].block_time, None);
+
+    assert_eq!(
+        read_log_file("Program log: x"),
+        Err(LogFileError::MissingHeader(1))
+    );
+    assert_eq!(
+        read_log_file("# komentarz\ntx abc"),
+        Err(LogFileError::InvalidHeader(2))
+    );
+    assert_eq!(
+        read_log_file("tx abc 1 2 3"),
+        Err(LogFileError::InvalidHeader(1))
+    );
+}
@@ -0,0 +1,245 @@
+// program/tests/lifecycle.rs
+mod common;
+
+use common::*;
+use solana_io::{
+    error::SolanaIoError,
+    instruction,
+    solana_io::{GlobalGame, PlayerState, Room},
+};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signer};
+
+#[tokio::test]
+async fn full_game_lifecycle() {
+    let mut env = start_initialized(2).await;
+    let game_address = env.game_address();
+    let game_rent = env.rent_minimum(GlobalGame::SIZE).await;
+    let room_address = env.room_address(ROOM);
+    let room_rent = env.rent_minimum(Room::SIZE).await;
+    let fee_vault_address = env.fee_vault_address();
+    let fee_vault_rent = env.rent_minimum(0).await;
+    let player_state_rent = env.rent_minimum(PlayerState::SIZE).await;
+    let alice = env.player(0);
+    let bob = env.player(1);
+
+    // InitializeGame + CreateRoom
+    let game = env.game().await;
+    assert!(game.is_initialized);
+    assert_eq!(game.server_authority, env.server.pubkey());
+    assert_eq!(env.lamports(&game_address).await, game_rent);
+    assert_eq!(env.lamports(&fee_vault_address).await, fee_vault_rent);
+
+    let room = env.room(ROOM).await;
+    assert!(room.is_initialized);
+    assert_eq!(room.room_id, ROOM);
+    assert_eq!(room.total_pool, 0);
+    assert_eq!(room.active_players, 0);
+    assert_eq!(room.min_stake, game.min_stake);
+    assert_eq!(room.max_stake, game.max_stake);
+    assert_eq!(env.lamports(&room_address).await, room_rent);
+
+    // JoinGame
+    let alice_stake = LAMPORTS_PER_SOL;
+    let bob_stake = LAMPORTS_PER_SOL / 2;
+    env.join(0, alice_stake).await.unwrap();
+    env.join(1, bob_stake).await.unwrap();
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.total_pool, alice_stake + bob_stake);
+    assert_eq!(room.active_players, 2);
+    assert_eq!(room.total_players, 2);
+    assert_eq!(
+        env.lamports(&room_address).await,
+        room_rent + alice_stake + bob_stake
+    );
+    // Stawki leżą w pokoju, konfiguracja ma tylko czynsz
+    assert_eq!(env.lamports(&game_address).await, game_rent);
+    assert_eq!(
+        env.lamports(&alice).await,
+        PLAYER_STARTING_BALANCE - alice_stake - player_state_rent
+    );
+
+    let alice_state = env.player_state(&alice).await.unwrap();
+    assert_eq!(alice_state.pubkey, alice);
+    assert!(alice_state.is_active);
+    assert_eq!(alice_state.stake_amount, alice_stake);
+    assert_eq!(alice_state.current_value, alice_stake);
+    assert_eq!(alice_state.room_id, ROOM);
+
+    // UpdatePlayerValue - Alice zjada Boba
+    env.eat(0, 1, bob_stake).await.unwrap();
+
+    let alice_state = env.player_state(&alice).await.unwrap();
+    assert!(alice_state.is_active);
+    assert_eq!(alice_state.current_value, alice_stake + bob_stake);
+    let bob_state = env.player_state(&bob).await.unwrap();
+    assert!(!bob_state.is_active);
+    assert_eq!(bob_state.current_value, 0);
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 1);
+    assert_eq!(room.total_pool, alice_stake + bob_stake);
+    assert_eq!(
+        env.lamports(&room_address).await,
+        room_rent + alice_stake + bob_stake
+    );
+
+    // CashOut - 5% prowizji zostaje w pokoju do CollectRoomFees
+    env.cash_out(0).await.unwrap();
+
+    let value = alice_stake + bob_stake;
+    let fee = value * 5 / 100;
+    let payout = value - fee;
+    assert_eq!(env.lamports(&room_address).await, room_rent + fee);
+    assert_eq!(env.lamports(&fee_vault_address).await, fee_vault_rent);
+    assert_eq!(
+        env.lamports(&PLATFORM_WALLET).await,
+        FEE_WALLET_STARTING_BALANCE
+    );
+    assert_eq!(
+        env.lamports(&alice).await,
+        PLAYER_STARTING_BALANCE - alice_stake - player_state_rent + payout
+    );
+
+    let alice_state = env.player_state(&alice).await.unwrap();
+    assert!(!alice_state.is_active);
+    assert_eq!(alice_state.current_value, 0);
+    assert_eq!(alice_state.total_earned, payout);
+    assert!(alice_state.last_cashout > 0);
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.total_pool, 0);
+    assert_eq!(room.active_players, 0);
+    assert_eq!(room.fee_collected, fee);
+    assert_eq!(env.game().await.platform_fee_collected, 0);
+
+    // Rejoin - konto stanu już istnieje, czynsz nie jest pobierany drugi raz
+    let rejoin_stake = LAMPORTS_PER_SOL / 5;
+    env.join(0, rejoin_stake).await.unwrap();
+
+    let alice_state = env.player_state(&alice).await.unwrap();
+    assert!(alice_state.is_active);
+    assert_eq!(alice_state.current_value, rejoin_stake);
+    assert_eq!(alice_state.stake_amount, alice_stake + rejoin_stake);
+    assert_eq!(
+        env.lamports(&alice).await,
+        PLAYER_STARTING_BALANCE - alice_stake - player_state_rent + payout - rejoin_stake
+    );
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.total_pool, rejoin_stake);
+    assert_eq!(room.active_players, 1);
+    assert_eq!(room.total_players, 2);
+    assert_eq!(
+        env.lamports(&room_address).await,
+        room_rent + fee + rejoin_stake
+    );
+
+    // ForceCleanup - bez wypłaty
+    let alice_balance = env.lamports(&alice).await;
+    env.force_cleanup(0).await.unwrap();
+
+    let alice_state = env.player_state(&alice).await.unwrap();
+    assert!(!alice_state.is_active);
+    assert_eq!(alice_state.current_value, 0);
+    assert_eq!(env.lamports(&alice).await, alice_balance);
+
+    assert_eq!(env.room(ROOM).await.active_players, 0);
+    assert_eq!(
+        env.lamports(&room_address).await,
+        room_rent + fee + rejoin_stake
+    );
+
+    // ForceCleanup nieaktywnego gracza to no-op
+    env.force_cleanup(0).await.unwrap();
+    assert_eq!(env.room(ROOM).await.active_players, 0);
+
+    // CollectRoomFees - prowizje pokoju przechodzą do skarbca
+    env.collect_room_fees(ROOM).await.unwrap();
+
+    assert_eq!(env.room(ROOM).await.fee_collected, 0);
+    assert_eq!(env.lamports(&room_address).await, room_rent + rejoin_stake);
+    assert_eq!(env.lamports(&fee_vault_address).await, fee_vault_rent + fee);
+    assert_eq!(env.game().await.platform_fee_collected, fee);
+
+    // WithdrawFees - zebrane prowizje trafiają do portfela platformy
+    env.withdraw_fees(&PLATFORM_WALLET, fee).await.unwrap();
+
+    assert_eq!(env.lamports(&fee_vault_address).await, fee_vault_rent);
+    assert_eq!(
+        env.lamports(&PLATFORM_WALLET).await,
+        FEE_WALLET_STARTING_BALANCE + fee
+    );
+    let game = env.game().await;
+    assert_eq!(game.platform_fee_collected, 0);
+    assert_eq!(game.total_fees_withdrawn, fee);
+    assert_eq!(env.lamports(&room_address).await, room_rent + rejoin_stake);
+}
+
+#[tokio::test]
+async fn platform_fee_wallet_is_configurable() {
+    let mut env = start_initialized(1).await;
+    let admin = env.admin.insecure_clone();
+    let new_wallet = Pubkey::new_unique();
+    assert_eq!(env.game().await.platform_fee_wallet, PLATFORM_WALLET);
+
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        None,
+        None,
+        None,
+        Some(new_wallet),
+        None,
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(env.game().await.platform_fee_wallet, new_wallet);
+
+    env.join(0, LAMPORTS_PER_SOL).await.unwrap();
+
+    // ForceCleanup nie dotyka portfela prowizji - zmiana portfela go nie blokuje
+    env.force_cleanup(0).await.unwrap();
+    assert!(!env.player_state(&env.player(0)).await.unwrap().is_active);
+}
+
+#[tokio::test]
+async fn fees_accrue_until_withdrawn() {
+    let mut env = start_initialized(2).await;
+    let fee_vault_address = env.fee_vault_address();
+    let fee_vault_rent = env.rent_minimum(0).await;
+    let recipient = Pubkey::new_unique();
+
+    let stake = LAMPORTS_PER_SOL;
+    env.join(0, stake).await.unwrap();
+    env.join(1, 2 * stake).await.unwrap();
+    env.cash_out(0).await.unwrap();
+    env.cash_out(1).await.unwrap();
+
+    let fee = stake * 5 / 100 + 2 * stake * 5 / 100;
+    assert_eq!(env.room(ROOM).await.fee_collected, fee);
+
+    // Niezebranych prowizji pokoju nie da się jeszcze wypłacić
+    let result = env.withdraw_fees(&recipient, 1).await;
+    assert_custom_error(result, SolanaIoError::InsufficientAccruedFees);
+
+    env.collect_room_fees(ROOM).await.unwrap();
+    assert_eq!(env.game().await.platform_fee_collected, fee);
+    assert_eq!(env.lamports(&fee_vault_address).await, fee_vault_rent + fee);
+
+    // Częściowa wypłata na dowolne konto
+    env.withdraw_fees(&recipient, fee / 3).await.unwrap();
+    assert_eq!(env.lamports(&recipient).await, fee / 3);
+
+    // Więcej niż zostało w skarbcu
+    let result = env.withdraw_fees(&recipient, fee - fee / 3 + 1).await;
+    assert_custom_error(result, SolanaIoError::InsufficientAccruedFees);
+
+    env.withdraw_fees(&recipient, fee - fee / 3).await.unwrap();
+    assert_eq!(env.lamports(&recipient).await, fee);
+    assert_eq!(env.lamports(&fee_vault_address).await, fee_vault_rent);
+
+    let game = env.game().await;
+    assert_eq!(game.platform_fee_collected, 0);
+    assert_eq!(game.total_fees_withdrawn, fee);
+    assert_eq!(env.room(ROOM).await.total_pool, 0);
+}
@@ -0,0 +1,323 @@
+// program/tests/multisig.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, instruction, instruction::AuthorityRole};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+/// Tworzy multisig dla roli i przełącza na niego rolę (propose + accept członkami)
+async fn install_multisig(
+    env: &mut TestEnv,
+    role: AuthorityRole,
+    members: &[&Keypair],
+    threshold: u8,
+) {
+    let admin = env.admin.insecure_clone();
+    let keys: Vec<Pubkey> = members.iter().map(|m| m.pubkey()).collect();
+    let multisig = instruction::multisig_address(&env.program_id, role);
+
+    let ix = instruction::create_multisig(
+        &env.program_id,
+        &admin.pubkey(),
+        &env.context.payer.pubkey(),
+        role,
+        &keys,
+        threshold,
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+
+    let ix = instruction::propose_authority(&env.program_id, &admin.pubkey(), role, &multisig);
+    env.process(&[ix], &[&admin]).await.unwrap();
+
+    let signing = &members[..threshold as usize];
+    let signing_keys: Vec<Pubkey> = signing.iter().map(|m| m.pubkey()).collect();
+    let ix = instruction::with_multisig_signers(
+        instruction::accept_authority(&env.program_id, &multisig, role),
+        &signing_keys,
+    );
+    env.process(&[ix], signing).await.unwrap();
+}
+
+fn eat_ix(env: &TestEnv, signers: &[&Keypair]) -> Instruction {
+    let multisig = instruction::multisig_address(&env.program_id, AuthorityRole::Server);
+    let keys: Vec<Pubkey> = signers.iter().map(|s| s.pubkey()).collect();
+    instruction::with_multisig_signers(
+        instruction::update_player_value(
+            &env.program_id,
+            &multisig,
+            ROOM,
+            &env.player(0),
+            &env.player(1),
+            STAKE,
+        ),
+        &keys,
+    )
+}
+
+#[tokio::test]
+async fn server_multisig_signs_gameplay() {
+    let mut env = start_initialized(3).await;
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let [a, b, c] = &members;
+    install_multisig(&mut env, AuthorityRole::Server, &[a, b, c], 2).await;
+
+    let multisig = instruction::multisig_address(&env.program_id, AuthorityRole::Server);
+    let game = env.game().await;
+    assert_eq!(game.server_authority, multisig);
+    let stored = env.multisig(AuthorityRole::Server).await;
+    assert_eq!(stored.threshold, 2);
+    assert_eq!(stored.members(), &[a.pubkey(), b.pubkey(), c.pubkey()]);
+
+    for i in 0..3 {
+        env.join(i, STAKE).await.unwrap();
+    }
+
+    // Stary pojedynczy klucz nie ma już uprawnień
+    let result = env.eat(0, 1, STAKE).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    // Jeden członek to za mało, ten sam członek dwa razy też
+    let ix = eat_ix(&env, &[a]);
+    let result = env.process(&[ix], &[a]).await;
+    assert_custom_error(result, SolanaIoError::MultisigThresholdNotMet);
+
+    let ix = eat_ix(&env, &[a, a]);
+    let result = env.process(&[ix], &[a]).await;
+    assert_custom_error(result, SolanaIoError::MultisigThresholdNotMet);
+
+    // Obcy podpis się nie liczy
+    let outsider = Keypair::new();
+    let ix = eat_ix(&env, &[a, &outsider]);
+    let result = env.process(&[ix], &[a, &outsider]).await;
+    assert_custom_error(result, SolanaIoError::MultisigThresholdNotMet);
+
+    let ix = eat_ix(&env, &[a, c]);
+    env.process(&[ix], &[a, c]).await.unwrap();
+    assert_eq!(
+        env.player_state(&env.player(0))
+            .await
+            .unwrap()
+            .current_value,
+        2 * STAKE
+    );
+
+    // ForceCleanup też przez multisig
+    let ix = instruction::with_multisig_signers(
+        instruction::force_cleanup(&env.program_id, &multisig, ROOM, &env.player(2)),
+        &[b.pubkey(), c.pubkey()],
+    );
+    env.process(&[ix], &[b, c]).await.unwrap();
+    assert!(!env.player_state(&env.player(2)).await.unwrap().is_active);
+    assert_eq!(env.room(ROOM).await.active_players, 1);
+
+    // Bilet wypłaty wymaga podpisów progu członków i konta multisig na końcu
+    let player = env.players[0].insecure_clone();
+    let ticket = env.cash_out_ticket(0).await;
+    let mut ixs = ticket_instructions(&env.program_id, &ticket, &[a]);
+    ixs[1]
+        .accounts
+        .push(AccountMeta::new_readonly(multisig, false));
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    // Bez konta multisig liczy się tylko klucz samego server authority
+    let ixs = ticket_instructions(&env.program_id, &ticket, &[a, b]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    let mut ixs = ticket_instructions(&env.program_id, &ticket, &[a, b]);
+    ixs[1]
+        .accounts
+        .push(AccountMeta::new_readonly(multisig, false));
+    env.process(&ixs, &[&player]).await.unwrap();
+    assert!(!env.player_state(&env.player(0)).await.unwrap().is_active);
+}
+
+#[tokio::test]
+async fn admin_multisig_updates_params_and_rotates_members() {
+    let mut env = start_initialized(0).await;
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let [a, b, c] = &members;
+    install_multisig(&mut env, AuthorityRole::Admin, &[a, b], 2).await;
+    let multisig = instruction::multisig_address(&env.program_id, AuthorityRole::Admin);
+
+    let ix = instruction::with_multisig_signers(
+        instruction::update_game_params(
+            &env.program_id,
+            &multisig,
+            None,
+            None,
+            Some(2),
+            None,
+            None,
+        ),
+        &[a.pubkey(), b.pubkey()],
+    );
+    env.process(&[ix], &[a, b]).await.unwrap();
+    assert_eq!(env.game().await.platform_fee_percent, 2);
+
+    // Rotacja: b odchodzi, dochodzi c, próg 2 z 2
+    let ix = instruction::with_multisig_signers(
+        instruction::set_multisig_signers(
+            &env.program_id,
+            &multisig,
+            AuthorityRole::Admin,
+            &[a.pubkey(), c.pubkey()],
+            2,
+        ),
+        &[a.pubkey(), b.pubkey()],
+    );
+    env.process(&[ix], &[a, b]).await.unwrap();
+    assert_eq!(
+        env.multisig(AuthorityRole::Admin).await.members(),
+        &[a.pubkey(), c.pubkey()]
+    );
+
+    let ix = instruction::with_multisig_signers(
+        instruction::update_game_params(
+            &env.program_id,
+            &multisig,
+            None,
+            None,
+            Some(4),
+            None,
+            None,
+        ),
+        &[a.pubkey(), b.pubkey()],
+    );
+    let result = env.process(&[ix], &[a, b]).await;
+    assert_custom_error(result, SolanaIoError::MultisigThresholdNotMet);
+
+    let ix = instruction::with_multisig_signers(
+        instruction::update_game_params(
+            &env.program_id,
+            &multisig,
+            None,
+            None,
+            Some(4),
+            None,
+            None,
+        ),
+        &[a.pubkey(), c.pubkey()],
+    );
+    env.process(&[ix], &[a, c]).await.unwrap();
+    assert_eq!(env.game().await.platform_fee_percent, 4);
+}
+
+#[tokio::test]
+async fn multisig_rejections() {
+    let mut env = start_initialized(0).await;
+    let admin = env.admin.insecure_clone();
+    let server = env.server.insecure_clone();
+    let payer = env.context.payer.pubkey();
+    let keys = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    let role = AuthorityRole::Server;
+
+    // Tylko admin tworzy multisig
+    let ix =
+        instruction::create_multisig(&env.program_id, &server.pubkey(), &payer, role, &keys, 2);
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    // Nieprawidłowe konfiguracje
+    let too_many: Vec<Pubkey> = (0..12).map(|_| Pubkey::new_unique()).collect();
+    let duplicated = [keys[0], keys[1], keys[0]];
+    let invalid: [(&[Pubkey], u8); 5] = [
+        (&[], 1),
+        (&keys, 0),
+        (&keys, 4),
+        (&duplicated, 2),
+        (&too_many, 2),
+    ];
+    for (signers, threshold) in invalid {
+        let ix = instruction::create_multisig(
+            &env.program_id,
+            &admin.pubkey(),
+            &payer,
+            role,
+            signers,
+            threshold,
+        );
+        let result = env.process(&[ix], &[&admin]).await;
+        assert_custom_error(result, SolanaIoError::InvalidMultisigConfig);
+    }
+
+    // Konto multisig, które nie jest PDA roli
+    let mut ix =
+        instruction::create_multisig(&env.program_id, &admin.pubkey(), &payer, role, &keys, 2);
+    ix.accounts[3].pubkey = instruction::multisig_address(&env.program_id, AuthorityRole::Admin);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::InvalidMultisigAccount);
+
+    let ix = instruction::create_multisig(&env.program_id, &admin.pubkey(), &payer, role, &keys, 2);
+    env.process(&[ix], &[&admin]).await.unwrap();
+    let ix = instruction::create_multisig(&env.program_id, &admin.pubkey(), &payer, role, &keys, 1);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::MultisigAlreadyExists);
+
+    // Rotacja tylko przez admina i z poprawną konfiguracją
+    let ix = instruction::set_multisig_signers(&env.program_id, &server.pubkey(), role, &keys, 1);
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    let ix =
+        instruction::set_multisig_signers(&env.program_id, &admin.pubkey(), role, &keys[..1], 2);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::InvalidMultisigConfig);
+    assert_eq!(env.multisig(role).await.threshold, 2);
+
+    // Multisig istnieje, ale rola nadal należy do pojedynczego klucza -
+    // podpisy członków nie zastąpią podpisu tego klucza
+    let member = Keypair::new();
+    let ix = instruction::set_multisig_signers(
+        &env.program_id,
+        &admin.pubkey(),
+        role,
+        &[member.pubkey()],
+        1,
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+    let multisig = instruction::multisig_address(&env.program_id, role);
+    let ix = instruction::with_multisig_signers(
+        instruction::update_game_params(
+            &env.program_id,
+            &multisig,
+            None,
+            None,
+            Some(1),
+            None,
+            None,
+        ),
+        &[member.pubkey()],
+    );
+    let result = env.process(&[ix], &[&member]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    // Klucz authority bez podpisu i bez multisig
+    let mut ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        None,
+        None,
+        Some(1),
+        None,
+        None,
+    );
+    ix.accounts[0].is_signer = false;
+    let result = env.process(&[ix], &[]).await;
+    assert_instruction_error(
+        result,
+        solana_sdk::instruction::InstructionError::MissingRequiredSignature,
+    );
+}
@@ -0,0 +1,125 @@
+// program/tests/partial.rs
+mod common;
+
+use common::*;
+use solana_io::{
+    error::SolanaIoError,
+    events::SolanaIoEvent,
+    instruction,
+    ticket::{ticket_message, TicketKind},
+};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn partial_cash_out_keeps_player_active() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    let server = env.server.insecure_clone();
+    env.join(0, 3 * STAKE).await.unwrap();
+    let nonce = env
+        .player_state(&player.pubkey())
+        .await
+        .unwrap()
+        .cashout_nonce;
+    let fee = STAKE * env.game().await.platform_fee_percent as u64 / 100;
+    let vault = env.lamports(&env.fee_vault_address()).await;
+
+    let ticket = env.partial_cash_out_ticket(0, STAKE).await;
+    let ixs = partial_ticket_instructions(&env.program_id, &ticket, &[&server]);
+    let before = env.lamports(&player.pubkey()).await;
+    let events = env.process_events(&ixs, &[&player]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerPartiallyCashedOut {
+            player: player.pubkey(),
+            room_id: ROOM,
+            amount: STAKE,
+            payout: STAKE - fee,
+            fee,
+            remaining_value: 2 * STAKE,
+        }]
+    );
+    assert_eq!(env.lamports(&player.pubkey()).await, before + STAKE - fee);
+    // Prowizja zostaje w pokoju do CollectRoomFees
+    assert_eq!(env.lamports(&env.fee_vault_address()).await, vault);
+    assert_eq!(env.game().await.platform_fee_collected, 0);
+
+    let state = env.player_state(&player.pubkey()).await.unwrap();
+    assert!(state.is_active);
+    assert_eq!(state.current_value, 2 * STAKE);
+    assert_eq!(state.total_earned, STAKE - fee);
+    assert_eq!(state.cashout_nonce, nonce + 1);
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 1);
+    assert_eq!(room.total_pool, 2 * STAKE);
+    assert_eq!(room.fee_collected, fee);
+
+    // Ten sam bilet nie zadziała drugi raz
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::CashOutTicketMismatch);
+
+    // Resztę gracz wypłaca zwykłym CashOut
+    env.cash_out(0).await.unwrap();
+    assert_eq!(env.room(ROOM).await.total_pool, 0);
+}
+
+#[tokio::test]
+async fn partial_cash_out_rejections() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    let server = env.server.insecure_clone();
+    env.join(0, 3 * STAKE).await.unwrap();
+    let min_stake = env.room(ROOM).await.min_stake;
+
+    // Po wypłacie gracz musi mieć co najmniej minimalną stawkę pokoju
+    let ticket = env
+        .partial_cash_out_ticket(0, 3 * STAKE - min_stake + 1)
+        .await;
+    let ixs = partial_ticket_instructions(&env.program_id, &ticket, &[&server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::PartialCashOutTooLarge);
+
+    let ticket = env.partial_cash_out_ticket(0, 0).await;
+    let ixs = partial_ticket_instructions(&env.program_id, &ticket, &[&server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::NothingToCashOut);
+
+    // Bilet podpisany przez gracza zamiast serwera
+    let ticket = env.partial_cash_out_ticket(0, STAKE).await;
+    let ixs = partial_ticket_instructions(&env.program_id, &ticket, &[&player]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    // Podpis pełnego biletu nie pasuje do częściowej wypłaty
+    let full = env.cash_out_ticket(0).await;
+    let ixs = [
+        signature_instruction(&full.message(&env.program_id), &[&server]),
+        instruction::partial_cash_out(&env.program_id, &player.pubkey(), ROOM, &ticket),
+    ];
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    // Treść biletu częściowego podpisana jako bilet innego rodzaju
+    let message = ticket_message(&env.program_id, TicketKind::CashOut, &ticket.body());
+    let ixs = [
+        signature_instruction(&message, &[&server]),
+        instruction::partial_cash_out(&env.program_id, &player.pubkey(), ROOM, &ticket),
+    ];
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    // Nonce z innego biletu
+    let mut stale = ticket.clone();
+    stale.nonce += 1;
+    let ixs = partial_ticket_instructions(&env.program_id, &stale, &[&server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidTicketNonce);
+
+    // Pełna wypłata unieważnia bilet częściowy
+    env.cash_out(0).await.unwrap();
+    let ixs = partial_ticket_instructions(&env.program_id, &ticket, &[&server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+}
@@ -0,0 +1,66 @@
+// program/tests/pause.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, instruction};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+async fn set_paused(env: &mut TestEnv, joins: bool, gameplay: bool, cashouts: bool) {
+    let admin = env.admin.insecure_clone();
+    let ix = instruction::set_paused(&env.program_id, &admin.pubkey(), joins, gameplay, cashouts);
+    env.process(&[ix], &[&admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn pause_flags_are_independent() {
+    let mut env = start_initialized(3).await;
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+
+    // Wstrzymane dołączanie - rozgrywka i wypłaty działają
+    set_paused(&mut env, true, false, false).await;
+    let game = env.game().await;
+    assert!(game.paused_joins && !game.paused_gameplay && !game.paused_cashouts);
+    let result = env.join(2, STAKE).await;
+    assert_custom_error(result, SolanaIoError::ProgramPaused);
+    env.eat(0, 1, STAKE).await.unwrap();
+
+    // Wstrzymana rozgrywka - UpdatePlayerValue i ForceCleanup
+    set_paused(&mut env, false, true, false).await;
+    env.join(2, STAKE).await.unwrap();
+    let result = env.eat(0, 2, STAKE).await;
+    assert_custom_error(result, SolanaIoError::ProgramPaused);
+    let result = env.force_cleanup(2).await;
+    assert_custom_error(result, SolanaIoError::ProgramPaused);
+
+    // Wstrzymane wypłaty
+    set_paused(&mut env, false, false, true).await;
+    let result = env.cash_out(0).await;
+    assert_custom_error(result, SolanaIoError::ProgramPaused);
+    assert!(env.player_state(&env.player(0)).await.unwrap().is_active);
+
+    // Wznowienie
+    set_paused(&mut env, false, false, false).await;
+    env.cash_out(0).await.unwrap();
+    env.force_cleanup(2).await.unwrap();
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 0);
+    assert_eq!(room.total_pool, STAKE);
+}
+
+#[tokio::test]
+async fn only_admin_can_pause() {
+    let mut env = start_initialized(1).await;
+    let server = env.server.insecure_clone();
+
+    let ix = instruction::set_paused(&env.program_id, &server.pubkey(), true, true, true);
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    let game = env.game().await;
+    assert!(!game.paused_joins && !game.paused_gameplay && !game.paused_cashouts);
+    env.join(0, STAKE).await.unwrap();
+}
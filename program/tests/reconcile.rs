@@ -0,0 +1,183 @@
+// program/tests/reconcile.rs
+mod common;
+
+use borsh::BorshDeserialize;
+use common::*;
+use solana_io::{
+    error::SolanaIoError,
+    events::SolanaIoEvent,
+    instruction::{self, reconciliation_address, MAX_RECONCILE_PAGE_SIZE},
+    solana_io::Reconciliation,
+};
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signer,
+};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+const OTHER_ROOM: u32 = 2;
+
+async fn pause_all(env: &mut TestEnv) {
+    let admin = env.admin.insecure_clone();
+    let ix = instruction::set_paused(&env.program_id, &admin.pubkey(), true, true, true);
+    env.process(&[ix], &[&admin]).await.unwrap();
+}
+
+fn reconcile(env: &TestEnv, players: &[Pubkey], start: bool, finish: bool) -> Instruction {
+    instruction::reconcile(
+        &env.program_id,
+        &env.admin.pubkey(),
+        &env.context.payer.pubkey(),
+        ROOM,
+        players,
+        start,
+        finish,
+    )
+    .unwrap()
+}
+
+async fn reconciliation(env: &mut TestEnv) -> Reconciliation {
+    let account = env
+        .account(&reconciliation_address(&env.program_id, ROOM))
+        .await;
+    Reconciliation::try_from_slice(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn reconcile_repairs_room_counters() {
+    let mut env = start_initialized(3).await;
+    let admin = env.admin.insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, 2 * STAKE).await.unwrap();
+    env.join(2, STAKE).await.unwrap();
+    env.cash_out(2).await.unwrap();
+
+    // ForceCleanup zostawia wartość gracza w puli - liczniki rozjeżdżają się z graczami
+    env.force_cleanup(0).await.unwrap();
+    let room = env.room(ROOM).await;
+    assert_eq!((room.active_players, room.total_pool), (1, 3 * STAKE));
+
+    pause_all(&mut env).await;
+    let players: Vec<Pubkey> = (0..3).map(|i| env.player(i)).collect();
+    let pages = instruction::reconcile_pages(
+        &env.program_id,
+        &admin.pubkey(),
+        &env.context.payer.pubkey(),
+        ROOM,
+        &players,
+        2,
+    );
+    assert_eq!(pages.len(), 2);
+    env.process(&pages[..1], &[&admin]).await.unwrap();
+
+    // Strona w toku: sumy w zapisie, liczniki pokoju jeszcze nietknięte
+    let record = reconciliation(&mut env).await;
+    assert!(record.in_progress);
+    assert_eq!(record.players_scanned, 2);
+    assert_eq!(env.room(ROOM).await.total_pool, 3 * STAKE);
+
+    let events = env.process_events(&pages[1..], &[&admin]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::RoomReconciled {
+            room_id: ROOM,
+            players_scanned: 3,
+            previous_active_players: 1,
+            active_players: 1,
+            previous_total_pool: 3 * STAKE,
+            total_pool: 2 * STAKE,
+        }]
+    );
+    let room = env.room(ROOM).await;
+    assert_eq!((room.active_players, room.total_pool), (1, 2 * STAKE));
+    let record = reconciliation(&mut env).await;
+    assert!(!record.in_progress);
+    assert_eq!(record.previous_total_pool, 3 * STAKE);
+
+    // Kolejne uzgodnienie zaczyna od zera w tym samym koncie - wynik się nie zmienia
+    for page in &pages {
+        env.process(std::slice::from_ref(page), &[&admin])
+            .await
+            .unwrap();
+    }
+    let room = env.room(ROOM).await;
+    assert_eq!((room.active_players, room.total_pool), (1, 2 * STAKE));
+    assert_eq!(
+        reconciliation(&mut env).await.previous_total_pool,
+        2 * STAKE
+    );
+}
+
+#[tokio::test]
+async fn reconcile_rejections() {
+    let mut env = start_initialized(3).await;
+    let admin = env.admin.insecure_clone();
+    env.create_room(OTHER_ROOM).await.unwrap();
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+    env.join_room(2, OTHER_ROOM, STAKE).await.unwrap();
+    let mut players = [env.player(0), env.player(1)];
+    players.sort();
+
+    let ix = reconcile(&env, &players, true, true);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::GameNotPaused);
+    pause_all(&mut env).await;
+
+    // Bez `start` nie ma czego kontynuować
+    let ix = reconcile(&env, &players, false, true);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::ReconciliationNotStarted);
+
+    // Tylko admin authority
+    let server = env.server.insecure_clone();
+    let mut ix = reconcile(&env, &players, true, true);
+    ix.accounts[0].pubkey = server.pubkey();
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    // Odwrócona kolejność i gracz policzony na poprzedniej stronie
+    let ix = reconcile(&env, &[players[1], players[0]], true, false);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::ReconcilePlayersNotSorted);
+    let ix = reconcile(&env, &players[1..], true, false);
+    env.process(&[ix], &[&admin]).await.unwrap();
+    let ix = reconcile(&env, &players[1..], false, true);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::ReconcilePlayersNotSorted);
+
+    // Gracz z innego pokoju
+    let ix = reconcile(&env, &[env.player(2)], true, true);
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::PlayerRoomMismatch);
+
+    let room = env.room(ROOM).await;
+    assert_eq!((room.active_players, room.total_pool), (2, 2 * STAKE));
+}
+
+#[test]
+fn reconcile_pages_clamp_page_size() {
+    let program_id = Pubkey::new_unique();
+    let admin = Pubkey::new_unique();
+    let players: Vec<Pubkey> = (0..50).map(|_| Pubkey::new_unique()).collect();
+
+    // Strona mieści najwyżej MAX_RECONCILE_PAGE_SIZE stanów graczy
+    let pages = instruction::reconcile_pages(&program_id, &admin, &admin, ROOM, &players, 1000);
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[0].accounts.len(), 7 + MAX_RECONCILE_PAGE_SIZE);
+    assert_eq!(pages[2].accounts.len(), 7 + 50 - 2 * MAX_RECONCILE_PAGE_SIZE);
+
+    let pages = instruction::reconcile_pages(&program_id, &admin, &admin, ROOM, &players, 0);
+    assert_eq!(pages.len(), 50);
+
+    // Pojedyncza strona ponad limit to błąd, nie panika
+    let result = instruction::reconcile(
+        &program_id,
+        &admin,
+        &admin,
+        ROOM,
+        &players[..MAX_RECONCILE_PAGE_SIZE + 1],
+        true,
+        true,
+    );
+    assert_eq!(result, Err(SolanaIoError::ReconcilePageTooLarge));
+}
@@ -0,0 +1,93 @@
+// program/tests/refund.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, events::SolanaIoEvent, instruction};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn force_refund_returns_value_with_or_without_fee() {
+    let mut env = start_initialized(2).await;
+    let server = env.server.insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+    let fee_percent = env.game().await.platform_fee_percent as u64;
+    let vault = env.lamports(&env.fee_vault_address()).await;
+
+    // Bez prowizji gracz dostaje całą wartość
+    let player = env.player(0);
+    let before = env.lamports(&player).await;
+    let ixs = [instruction::force_refund(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &player,
+        false,
+    )];
+    let events = env.process_events(&ixs, &[&server]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerRefunded {
+            player,
+            room_id: ROOM,
+            value: STAKE,
+            payout: STAKE,
+            fee: 0,
+        }]
+    );
+    assert_eq!(env.lamports(&player).await, before + STAKE);
+    let state = env.player_state(&player).await.unwrap();
+    assert!(!state.is_active);
+    assert_eq!(state.current_value, 0);
+    assert_eq!(state.total_earned, STAKE);
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 1);
+    assert_eq!(room.total_pool, STAKE);
+    assert_eq!(env.lamports(&env.fee_vault_address()).await, vault);
+
+    // Z prowizją - jak przy zwykłej wypłacie
+    let player = env.player(1);
+    let before = env.lamports(&player).await;
+    env.force_refund(1, true).await.unwrap();
+    let fee = STAKE * fee_percent / 100;
+    assert_eq!(env.lamports(&player).await, before + STAKE - fee);
+    // Prowizja zostaje w pokoju do CollectRoomFees
+    assert_eq!(env.lamports(&env.fee_vault_address()).await, vault);
+    assert_eq!(env.game().await.platform_fee_collected, 0);
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 0);
+    assert_eq!(room.total_pool, 0);
+    assert_eq!(room.fee_collected, fee);
+}
+
+#[tokio::test]
+async fn force_refund_rejections() {
+    let mut env = start_initialized(2).await;
+    let player = env.players[0].insecure_clone();
+    let other = env.players[1].insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+
+    // Tylko server authority
+    let ixs = [instruction::force_refund(
+        &env.program_id,
+        &other.pubkey(),
+        ROOM,
+        &player.pubkey(),
+        false,
+    )];
+    let result = env.process(&ixs, &[&other]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    // Bilet wystawiony przed awarią nie działa po zwrocie, także po ponownym dołączeniu
+    let ticket = env.cash_out_ticket(0).await;
+    env.force_refund(0, false).await.unwrap();
+    let result = env.force_refund(0, false).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+
+    env.join(0, STAKE).await.unwrap();
+    let ixs = ticket_instructions(&env.program_id, &ticket, &[&env.server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidTicketNonce);
+}
@@ -0,0 +1,370 @@
+// program/tests/rejections.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, instruction, solana_io::GlobalGame};
+use solana_sdk::{
+    instruction::InstructionError,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn initialize_game_rejections() {
+    let mut env = start(0).await;
+    let server = env.server.pubkey();
+    let admin = env.admin.pubkey();
+    let payer = env.context.payer.pubkey();
+
+    // Initializer bez podpisu
+    let mut ix = instruction::initialize_game(&env.program_id, &payer, &server, &admin, &PLATFORM_WALLET);
+    ix.accounts[0].pubkey = server;
+    ix.accounts[0].is_signer = false;
+    let result = env.process(&[ix], &[]).await;
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+
+    // Konto gry, które nie jest PDA
+    let mut ix = instruction::initialize_game(&env.program_id, &payer, &server, &admin, &PLATFORM_WALLET);
+    ix.accounts[1].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[]).await;
+    assert_custom_error(result, SolanaIoError::InvalidGameAccount);
+
+    // Druga inicjalizacja
+    let ix = instruction::initialize_game(&env.program_id, &payer, &server, &admin, &PLATFORM_WALLET);
+    env.process(&[ix], &[]).await.unwrap();
+    let ix = instruction::initialize_game(
+        &env.program_id,
+        &payer,
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &PLATFORM_WALLET,
+    );
+    let result = env.process(&[ix], &[]).await;
+    assert_custom_error(result, SolanaIoError::GameAlreadyInitialized);
+    let game = env.game().await;
+    assert_eq!(game.server_authority, server);
+    assert_eq!(game.admin_authority, admin);
+}
+
+#[tokio::test]
+async fn join_game_rejections() {
+    let mut env = start_initialized(2).await;
+    let room = env.room(ROOM).await;
+
+    let result = env.join(0, room.min_stake - 1).await;
+    assert_custom_error(result, SolanaIoError::StakeBelowMinimum);
+
+    let result = env.join(0, room.max_stake + 1).await;
+    assert_custom_error(result, SolanaIoError::StakeAboveMaximum);
+
+    // Stan gracza pod cudzym PDA
+    let mut ix = instruction::join_game(&env.program_id, &env.player(0), ROOM, STAKE);
+    ix.accounts[1].pubkey = env.player_state_address(&env.player(1));
+    let player = env.players[0].insecure_clone();
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+
+    env.join(0, STAKE).await.unwrap();
+    let result = env.join(0, STAKE).await;
+    assert_custom_error(result, SolanaIoError::PlayerAlreadyActive);
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 1);
+    assert_eq!(room.total_pool, STAKE);
+}
+
+#[tokio::test]
+async fn update_player_value_rejections() {
+    let mut env = start_initialized(3).await;
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+
+    // Podpis kogoś innego niż server authority
+    let impostor = Keypair::new();
+    let ix = instruction::update_player_value(
+        &env.program_id,
+        &impostor.pubkey(),
+        ROOM,
+        &env.player(0),
+        &env.player(1),
+        STAKE,
+    );
+    let result = env.process(&[ix], &[&impostor]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    // Server authority bez podpisu
+    let mut ix = instruction::update_player_value(
+        &env.program_id,
+        &env.server.pubkey(),
+        ROOM,
+        &env.player(0),
+        &env.player(1),
+        STAKE,
+    );
+    ix.accounts[0].is_signer = false;
+    let result = env.process(&[ix], &[]).await;
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+
+    env.eat(0, 1, STAKE).await.unwrap();
+
+    // Bob już został zjedzony
+    let result = env.eat(0, 1, STAKE).await;
+    assert_custom_error(result, SolanaIoError::EatenPlayerNotActive);
+
+    // Zjedzony gracz nie może jeść
+    env.join(2, STAKE).await.unwrap();
+    let result = env.eat(1, 2, STAKE).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+
+    assert_eq!(env.room(ROOM).await.active_players, 2);
+    assert_eq!(env.player_state(&env.player(0)).await.unwrap().current_value, 2 * STAKE);
+}
+
+#[tokio::test]
+async fn cash_out_rejections() {
+    let mut env = start_initialized(2).await;
+    env.join(0, STAKE).await.unwrap();
+
+    // Gracz bez podpisu
+    let mut ixs = env.cash_out_instructions(0).await;
+    ixs[1].accounts[0].is_signer = false;
+    let result = env.process(&ixs, &[]).await;
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+
+    env.cash_out(0).await.unwrap();
+    let result = env.cash_out(0).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+
+    // Zjedzony gracz nie może wypłacić
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+    env.eat(0, 1, STAKE).await.unwrap();
+    let result = env.cash_out(1).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.total_pool, 2 * STAKE);
+    assert_eq!(room.active_players, 1);
+}
+
+#[tokio::test]
+async fn update_game_params_rejections() {
+    let mut env = start_initialized(0).await;
+    let admin = env.admin.insecure_clone();
+    let server = env.server.insecure_clone();
+    let before = env.game().await;
+
+    let impostor = Keypair::new();
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &impostor.pubkey(),
+        Some(1),
+        None,
+        None,
+        None,
+        None,
+    );
+    let result = env.process(&[ix], &[&impostor]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    // Gorący klucz serwera nie może zmieniać parametrów
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &server.pubkey(),
+        None,
+        None,
+        Some(10),
+        None,
+        None,
+    );
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        None,
+        None,
+        Some(11),
+        None,
+        None,
+    );
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::FeePercentTooHigh);
+
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        Some(before.max_stake + 1),
+        None,
+        None,
+        None,
+        None,
+    );
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::InvalidStakeRange);
+
+    let after: GlobalGame = env.game().await;
+    assert_eq!(after.min_stake, before.min_stake);
+    assert_eq!(after.max_stake, before.max_stake);
+    assert_eq!(after.platform_fee_percent, before.platform_fee_percent);
+
+    // Poprawna zmiana przechodzi
+    let ix = instruction::update_game_params(
+        &env.program_id,
+        &admin.pubkey(),
+        Some(STAKE),
+        Some(2 * STAKE),
+        Some(10),
+        None,
+        None,
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+    let after = env.game().await;
+    assert_eq!(after.min_stake, STAKE);
+    assert_eq!(after.max_stake, 2 * STAKE);
+    assert_eq!(after.platform_fee_percent, 10);
+}
+
+#[tokio::test]
+async fn force_cleanup_rejections() {
+    let mut env = start_initialized(2).await;
+    env.join(0, STAKE).await.unwrap();
+    let server = env.server.insecure_clone();
+
+    let impostor = Keypair::new();
+    let ix = instruction::force_cleanup(
+        &env.program_id,
+        &impostor.pubkey(),
+        ROOM,
+        &env.player(0),
+    );
+    let result = env.process(&[ix], &[&impostor]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedServerAuthority);
+
+    // Konto gracza inne niż w instrukcji
+    let mut ix = instruction::force_cleanup(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &env.player(0),
+    );
+    ix.accounts[4].pubkey = env.player(1);
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::PlayerAccountMismatch);
+
+    // Stan gracza pod cudzym PDA
+    env.join(1, STAKE).await.unwrap();
+    let mut ix = instruction::force_cleanup(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &env.player(0),
+    );
+    ix.accounts[1].pubkey = env.player_state_address(&env.player(1));
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+
+    assert!(env.player_state(&env.player(0)).await.unwrap().is_active);
+    assert!(env.player_state(&env.player(1)).await.unwrap().is_active);
+    assert_eq!(env.room(ROOM).await.active_players, 2);
+}
+
+#[tokio::test]
+async fn update_player_value_conservation() {
+    let mut env = start_initialized(3).await;
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, 2 * STAKE).await.unwrap();
+    env.join(2, STAKE).await.unwrap();
+    let server = env.server.insecure_clone();
+
+    // Serwer nie może przenieść więcej (ani mniej) niż zjedzony gracz ma on-chain
+    let result = env.eat(0, 1, 10 * STAKE).await;
+    assert_custom_error(result, SolanaIoError::EatenValueMismatch);
+    let result = env.eat(0, 1, STAKE).await;
+    assert_custom_error(result, SolanaIoError::EatenValueMismatch);
+
+    let result = env.eat(0, 0, STAKE).await;
+    assert_custom_error(result, SolanaIoError::SelfEatNotAllowed);
+
+    // Konto zjedzonego gracza należy do kogoś innego niż eaten_player
+    let mut ix = instruction::update_player_value(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &env.player(0),
+        &env.player(1),
+        2 * STAKE,
+    );
+    ix.accounts[2].pubkey = env.player_state_address(&env.player(2));
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+
+    // To samo dla gracza, który je
+    let mut ix = instruction::update_player_value(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &env.player(0),
+        &env.player(1),
+        2 * STAKE,
+    );
+    ix.accounts[1].pubkey = env.player_state_address(&env.player(2));
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+
+    env.eat(0, 1, 2 * STAKE).await.unwrap();
+    assert_eq!(env.player_state(&env.player(0)).await.unwrap().current_value, 3 * STAKE);
+    assert_eq!(env.player_state(&env.player(1)).await.unwrap().current_value, 0);
+    assert_eq!(env.player_state(&env.player(2)).await.unwrap().current_value, STAKE);
+    assert_eq!(env.room(ROOM).await.total_pool, 4 * STAKE);
+}
+
+#[tokio::test]
+async fn withdraw_fees_rejections() {
+    let mut env = start_initialized(1).await;
+    env.join(0, STAKE).await.unwrap();
+    env.cash_out(0).await.unwrap();
+    let fee = STAKE * 5 / 100;
+    let recipient = Pubkey::new_unique();
+
+    // Skarbiec podmieniony na konto programu, które nie jest PDA skarbca
+    let forged_vault = Pubkey::new_unique();
+    let vault_account = env.account(&env.fee_vault_address()).await;
+    env.set_account(&forged_vault, vault_account);
+    let mut ix = instruction::collect_room_fees(&env.program_id, ROOM);
+    ix.accounts[2].pubkey = forged_vault;
+    let result = env.process(&[ix], &[]).await;
+    assert_custom_error(result, SolanaIoError::InvalidFeeVaultAccount);
+    assert_eq!(env.room(ROOM).await.fee_collected, fee);
+
+    env.collect_room_fees(ROOM).await.unwrap();
+
+    // Tylko admin authority - również nie gorący klucz serwera
+    let server = env.server.insecure_clone();
+    let ix = instruction::withdraw_fees(&env.program_id, &server.pubkey(), &recipient, fee);
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    let impostor = Keypair::new();
+    let ix = instruction::withdraw_fees(&env.program_id, &impostor.pubkey(), &recipient, fee);
+    let result = env.process(&[ix], &[&impostor]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+
+    // Nie można wypłacić puli graczy ani czynszu skarbca
+    let result = env.withdraw_fees(&recipient, fee + 1).await;
+    assert_custom_error(result, SolanaIoError::InsufficientAccruedFees);
+
+    // Skarbiec podmieniony na konto gry
+    let admin = env.admin.insecure_clone();
+    let mut ix = instruction::withdraw_fees(&env.program_id, &admin.pubkey(), &recipient, fee);
+    ix.accounts[2].pubkey = env.game_address();
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::DuplicateAccount);
+
+    assert_eq!(env.game().await.platform_fee_collected, fee);
+    assert_eq!(env.lamports(&recipient).await, 0);
+}
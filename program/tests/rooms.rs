@@ -0,0 +1,153 @@
+// program/tests/rooms.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, instruction, solana_io::Room};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+const OTHER_ROOM: u32 = 2;
+
+#[tokio::test]
+async fn rooms_keep_separate_pools() {
+    let mut env = start_initialized(4).await;
+    env.create_room(OTHER_ROOM).await.unwrap();
+    let room_rent = env.rent_minimum(Room::SIZE).await;
+    let server = env.server.insecure_clone();
+
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+    env.join_room(2, OTHER_ROOM, 2 * STAKE).await.unwrap();
+    env.join_room(3, OTHER_ROOM, 3 * STAKE).await.unwrap();
+
+    let room = env.room(ROOM).await;
+    assert_eq!(room.total_pool, 2 * STAKE);
+    assert_eq!(room.active_players, 2);
+    let other = env.room(OTHER_ROOM).await;
+    assert_eq!(other.total_pool, 5 * STAKE);
+    assert_eq!(other.active_players, 2);
+    assert_eq!(
+        env.lamports(&env.room_address(OTHER_ROOM)).await,
+        room_rent + 5 * STAKE
+    );
+    assert_eq!(
+        env.player_state(&env.player(2)).await.unwrap().room_id,
+        OTHER_ROOM
+    );
+
+    // Gracz z innego pokoju nie może zostać zjedzony
+    let result = env.eat(0, 2, 2 * STAKE).await;
+    assert_custom_error(result, SolanaIoError::PlayerRoomMismatch);
+
+    // Zjedzenie w drugim pokoju nie dotyka pierwszego
+    let ix = instruction::update_player_value(
+        &env.program_id,
+        &server.pubkey(),
+        OTHER_ROOM,
+        &env.player(3),
+        &env.player(2),
+        2 * STAKE,
+    );
+    env.process(&[ix], &[&server]).await.unwrap();
+    assert_eq!(env.room(OTHER_ROOM).await.active_players, 1);
+    assert_eq!(env.room(ROOM).await.active_players, 2);
+
+    // Wypłata z cudzego pokoju jest odrzucana
+    let mut ixs = env.cash_out_instructions(3).await;
+    ixs[1].accounts[3].pubkey = env.room_address(ROOM);
+    let player = env.players[3].insecure_clone();
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::PlayerRoomMismatch);
+
+    let ticket = env.cash_out_ticket(3).await;
+    let mut ixs = ticket_instructions(&env.program_id, &ticket, &[&server]);
+    ixs[1] = instruction::cash_out(&env.program_id, &player.pubkey(), OTHER_ROOM, &ticket, false);
+    env.process(&ixs, &[&player]).await.unwrap();
+    // Prowizja zostaje w pokoju gracza, konfiguracja nie jest zapisywana
+    let fee = 5 * STAKE * 5 / 100;
+    assert_eq!(
+        env.lamports(&env.room_address(OTHER_ROOM)).await,
+        room_rent + fee
+    );
+    let other = env.room(OTHER_ROOM).await;
+    assert_eq!(other.total_pool, 0);
+    assert_eq!(other.fee_collected, fee);
+    assert_eq!(env.room(ROOM).await.total_pool, 2 * STAKE);
+    assert_eq!(env.room(ROOM).await.fee_collected, 0);
+    assert_eq!(env.game().await.platform_fee_collected, 0);
+
+    // Zbieranie prowizji pokoju bez prowizji nic nie zmienia
+    env.collect_room_fees(ROOM).await.unwrap();
+    assert_eq!(env.game().await.platform_fee_collected, 0);
+    env.collect_room_fees(OTHER_ROOM).await.unwrap();
+    assert_eq!(env.game().await.platform_fee_collected, fee);
+    assert_eq!(env.lamports(&env.room_address(OTHER_ROOM)).await, room_rent);
+    assert_eq!(env.room(OTHER_ROOM).await.fee_collected, 0);
+
+    // Po wypłacie gracz może przejść do innego pokoju
+    env.join(3, STAKE).await.unwrap();
+    let room = env.room(ROOM).await;
+    assert_eq!(room.active_players, 3);
+    assert_eq!(room.total_players, 3);
+    assert_eq!(
+        env.player_state(&env.player(3)).await.unwrap().room_id,
+        ROOM
+    );
+}
+
+#[tokio::test]
+async fn room_management() {
+    let mut env = start_initialized(1).await;
+    let admin = env.admin.insecure_clone();
+    let payer = env.context.payer.pubkey();
+
+    // Tylko admin tworzy pokoje, każdy id raz
+    let server = env.server.insecure_clone();
+    let ix = instruction::create_room(&env.program_id, &server.pubkey(), &payer, OTHER_ROOM);
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+    let result = env.create_room(ROOM).await;
+    assert_custom_error(result, SolanaIoError::RoomAlreadyExists);
+
+    // Nieistniejący pokój
+    let result = env.join_room(0, OTHER_ROOM, STAKE).await;
+    assert_custom_error(result, SolanaIoError::InvalidAccountOwner);
+
+    // Konto pokoju niezgodne z room_id z instrukcji
+    let mut ix = instruction::join_game(&env.program_id, &env.player(0), OTHER_ROOM, STAKE);
+    ix.accounts[3].pubkey = env.room_address(ROOM);
+    let player = env.players[0].insecure_clone();
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidRoomAccount);
+
+    // Limity stawek są per pokój
+    env.create_room(OTHER_ROOM).await.unwrap();
+    let ix = instruction::update_room_params(
+        &env.program_id,
+        &admin.pubkey(),
+        OTHER_ROOM,
+        Some(5 * STAKE),
+        None,
+    );
+    env.process(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(env.room(OTHER_ROOM).await.min_stake, 5 * STAKE);
+    assert_eq!(env.room(ROOM).await.min_stake, env.game().await.min_stake);
+
+    let result = env.join_room(0, OTHER_ROOM, STAKE).await;
+    assert_custom_error(result, SolanaIoError::StakeBelowMinimum);
+    env.join(0, STAKE).await.unwrap();
+
+    let ix = instruction::update_room_params(
+        &env.program_id,
+        &admin.pubkey(),
+        OTHER_ROOM,
+        None,
+        Some(STAKE),
+    );
+    let result = env.process(&[ix], &[&admin]).await;
+    assert_custom_error(result, SolanaIoError::InvalidStakeRange);
+
+    let ix = instruction::update_room_params(&env.program_id, &server.pubkey(), ROOM, None, None);
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::UnauthorizedAdminAuthority);
+}
@@ -0,0 +1,94 @@
+// program/tests/solvency.rs
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use solana_io::{error::SolanaIoError, instruction};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+const OTHER_ROOM: u32 = 2;
+
+#[tokio::test]
+async fn check_solvency_reports_shortfalls() {
+    let mut env = start_initialized(2).await;
+    env.create_room(OTHER_ROOM).await.unwrap();
+    env.join(0, STAKE).await.unwrap();
+    env.join_room(1, OTHER_ROOM, 2 * STAKE).await.unwrap();
+    env.cash_out(0).await.unwrap();
+
+    let ixs = [instruction::check_solvency(
+        &env.program_id,
+        &[ROOM, OTHER_ROOM],
+    )];
+    env.process(&ixs, &[]).await.unwrap();
+
+    // Pokój traci lamporty poza programem - pula nie jest już pokryta
+    let room_address = env.room_address(OTHER_ROOM);
+    let mut room_account = env.account(&room_address).await;
+    room_account.lamports -= 1;
+    env.set_account(&room_address, room_account);
+    let result = env.process(&ixs, &[]).await;
+    assert_custom_error(result, SolanaIoError::PoolInsolvent);
+
+    // Pozostałe konta nadal są wypłacalne
+    let ix = instruction::check_solvency(&env.program_id, &[ROOM]);
+    env.process(&[ix], &[]).await.unwrap();
+
+    // Pokój trzyma też niezebrane prowizje - po ich zebraniu pilnuje ich skarbiec
+    let ixs = [instruction::check_solvency(&env.program_id, &[ROOM])];
+    let room_address = env.room_address(ROOM);
+    let mut room_account = env.account(&room_address).await;
+    room_account.lamports -= 1;
+    env.set_account(&room_address, room_account.clone());
+    let result = env.process(&ixs, &[]).await;
+    assert_custom_error(result, SolanaIoError::PoolInsolvent);
+    room_account.lamports += 1;
+    env.set_account(&room_address, room_account);
+    env.collect_room_fees(ROOM).await.unwrap();
+    env.process(&ixs, &[]).await.unwrap();
+
+    // Skarbiec z mniejszym saldem niż zapisane prowizje
+    let vault_address = env.fee_vault_address();
+    let mut vault_account = env.account(&vault_address).await;
+    vault_account.lamports -= 1;
+    env.set_account(&vault_address, vault_account);
+    let ix = instruction::check_solvency(&env.program_id, &[]);
+    let result = env.process(&[ix], &[]).await;
+    assert_custom_error(result, SolanaIoError::PoolInsolvent);
+    let result = env.withdraw_fees(&Pubkey::new_unique(), 1).await;
+    assert_custom_error(result, SolanaIoError::PoolInsolvent);
+}
+
+#[tokio::test]
+async fn payouts_cannot_exceed_pool() {
+    let mut env = start_initialized(2).await;
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+
+    // Zapisana pula mniejsza niż wartość gracza (np. błędne zaksięgowanie)
+    let room_address = env.room_address(ROOM);
+    let mut room_account = env.account(&room_address).await;
+    let mut room = env.room(ROOM).await;
+    room.total_pool = STAKE / 2;
+    room_account.data = room.try_to_vec().unwrap();
+    env.set_account(&room_address, room_account);
+
+    let before = env.lamports(&room_address).await;
+    let result = env.cash_out(0).await;
+    assert_custom_error(result, SolanaIoError::PoolInsolvent);
+    assert_eq!(env.lamports(&room_address).await, before);
+
+    // EmergencyWithdraw pilnuje tego samego niezmiennika
+    let timeout = env.game().await.heartbeat_timeout;
+    env.advance_clock(timeout + 1).await;
+    let player = env.players[1].insecure_clone();
+    let ix = instruction::emergency_withdraw(&env.program_id, &player.pubkey(), ROOM);
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::PoolInsolvent);
+    assert_eq!(env.lamports(&room_address).await, before);
+
+    let ix = instruction::check_solvency(&env.program_id, &[ROOM]);
+    env.process(&[ix], &[]).await.unwrap();
+    assert_eq!(env.room(ROOM).await.total_pool, STAKE / 2);
+}
@@ -0,0 +1,162 @@
+// program/tests/ticket.rs
+mod common;
+
+use common::*;
+use solana_io::{
+    error::SolanaIoError,
+    instruction,
+    ticket::{ed25519_instruction, ticket_message, CashOutTicket, TicketKind, TICKET_DOMAIN},
+};
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signer,
+};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+/// CashOut z podpisem serwera pod dowolną wiadomością zamiast `ticket.message`
+fn signed_cash_out(env: &TestEnv, ticket: &CashOutTicket, message: &[u8]) -> Vec<Instruction> {
+    let signature: [u8; 64] = env.server.sign_message(message).into();
+    vec![
+        ed25519_instruction(&[(env.server.pubkey(), signature)], message),
+        instruction::cash_out(&env.program_id, &ticket.player, ROOM, ticket, false),
+    ]
+}
+
+#[tokio::test]
+async fn ticket_cannot_be_replayed() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+
+    let ixs = env.cash_out_instructions(0).await;
+    let nonce = env
+        .player_state(&player.pubkey())
+        .await
+        .unwrap()
+        .cashout_nonce;
+    env.process(&ixs, &[&player]).await.unwrap();
+    assert_eq!(
+        env.player_state(&player.pubkey())
+            .await
+            .unwrap()
+            .cashout_nonce,
+        nonce + 1
+    );
+
+    // Po ponownym dołączeniu stary bilet (ta sama kwota) ma już zużyty nonce
+    env.join(0, STAKE).await.unwrap();
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidTicketNonce);
+
+    env.cash_out(0).await.unwrap();
+    assert_eq!(
+        env.player_state(&player.pubkey())
+            .await
+            .unwrap()
+            .cashout_nonce,
+        nonce + 2
+    );
+}
+
+#[tokio::test]
+async fn ticket_must_match_player_state() {
+    let mut env = start_initialized(2).await;
+    let player = env.players[0].insecure_clone();
+    let server = env.server.insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+
+    // Bilet wystawiony przed zjedzeniem - wartość on-chain już się zmieniła
+    let stale = env.cash_out_ticket(0).await;
+    env.eat(0, 1, STAKE).await.unwrap();
+    let ixs = ticket_instructions(&env.program_id, &stale, &[&server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::CashOutTicketMismatch);
+
+    // Zawyżona kwota
+    let mut ticket = env.cash_out_ticket(0).await;
+    ticket.amount += 1;
+    let ixs = ticket_instructions(&env.program_id, &ticket, &[&server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::CashOutTicketMismatch);
+
+    // Przeterminowany bilet
+    let ticket = env.cash_out_ticket(0).await;
+    env.advance_clock(61).await;
+    let ixs = ticket_instructions(&env.program_id, &ticket, &[&server]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::CashOutTicketExpired);
+
+    assert!(env.player_state(&player.pubkey()).await.unwrap().is_active);
+    env.cash_out(0).await.unwrap();
+}
+
+#[tokio::test]
+async fn ticket_must_be_signed_by_server() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+    let ticket = env.cash_out_ticket(0).await;
+
+    // Podpis gracza zamiast serwera
+    let ixs = ticket_instructions(&env.program_id, &ticket, &[&player]);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    // Brak instrukcji ed25519
+    let ix = instruction::cash_out(&env.program_id, &player.pubkey(), ROOM, &ticket, false);
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    // Serwer podpisał inny bilet niż ten w CashOut
+    let mut signed = ticket.clone();
+    signed.expiry += 1;
+    let mut ixs = ticket_instructions(&env.program_id, &signed, &[&env.server]);
+    ixs[1] = instruction::cash_out(&env.program_id, &player.pubkey(), ROOM, &ticket, false);
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    // Instrukcja ed25519 musi stać bezpośrednio przed CashOut
+    let mut ixs = ticket_instructions(&env.program_id, &ticket, &[&env.server]);
+    ixs.insert(
+        1,
+        instruction::heartbeat(&env.program_id, &env.server.pubkey()),
+    );
+    let server = env.server.insecure_clone();
+    let result = env.process(&ixs, &[&player, &server]).await;
+    assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+
+    env.cash_out(0).await.unwrap();
+}
+
+#[tokio::test]
+async fn ticket_message_is_bound_to_domain_program_and_kind() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+    let ticket = env.cash_out_ticket(0).await;
+    let body = ticket.body();
+
+    let with_prefix = |domain: &[u8], program_id: &Pubkey, kind: u8| {
+        [domain, program_id.as_ref(), &[kind], &body].concat()
+    };
+    let cash_out_kind = TicketKind::CashOut as u8;
+    let forged = [
+        // Same bajty biletu, bez prefiksu
+        body.clone(),
+        // Podpis z innej domeny
+        with_prefix(b"other-app:ticket:v1", &env.program_id, cash_out_kind),
+        // Bilet dla innego wdrożenia programu
+        ticket_message(&Pubkey::new_unique(), TicketKind::CashOut, &body),
+        // Inny rodzaj biletu
+        with_prefix(TICKET_DOMAIN, &env.program_id, cash_out_kind + 1),
+    ];
+    for message in forged {
+        let ixs = signed_cash_out(&env, &ticket, &message);
+        let result = env.process(&ixs, &[&player]).await;
+        assert_custom_error(result, SolanaIoError::InvalidCashOutTicket);
+    }
+
+    let ixs = signed_cash_out(&env, &ticket, &ticket.message(&env.program_id));
+    env.process(&ixs, &[&player]).await.unwrap();
+}
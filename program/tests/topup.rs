@@ -0,0 +1,130 @@
+// program/tests/topup.rs
+mod common;
+
+use common::*;
+use solana_io::{error::SolanaIoError, events::SolanaIoEvent, instruction};
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, signature::Signer};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn top_up_adds_to_stake_and_pool() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    env.join(0, STAKE).await.unwrap();
+
+    let ixs = [instruction::top_up(
+        &env.program_id,
+        &player.pubkey(),
+        ROOM,
+        STAKE,
+    )];
+    let before = env.lamports(&player.pubkey()).await;
+    let events = env.process_events(&ixs, &[&player]).await;
+    assert_eq!(
+        events,
+        vec![SolanaIoEvent::PlayerToppedUp {
+            player: player.pubkey(),
+            room_id: ROOM,
+            amount: STAKE,
+            session_stake: 2 * STAKE,
+            current_value: 2 * STAKE,
+        }]
+    );
+    // Opłatę transakcji płaci payer kontekstu
+    assert_eq!(env.lamports(&player.pubkey()).await, before - STAKE);
+
+    let state = env.player_state(&player.pubkey()).await.unwrap();
+    assert!(state.is_active);
+    assert_eq!(state.stake_amount, 2 * STAKE);
+    assert_eq!(state.session_stake, 2 * STAKE);
+    assert_eq!(state.current_value, 2 * STAKE);
+    let room = env.room(ROOM).await;
+    assert_eq!(room.total_pool, 2 * STAKE);
+    assert_eq!(room.active_players, 1);
+
+    // Wypłata obejmuje dopłatę
+    env.cash_out(0).await.unwrap();
+    assert_eq!(env.room(ROOM).await.total_pool, 0);
+}
+
+#[tokio::test]
+async fn top_up_is_capped_per_session() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    let max_stake = env.room(ROOM).await.max_stake;
+    env.join(0, max_stake - STAKE).await.unwrap();
+
+    let ixs = [instruction::top_up(
+        &env.program_id,
+        &player.pubkey(),
+        ROOM,
+        STAKE + 1,
+    )];
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::StakeAboveMaximum);
+
+    let ixs = [instruction::top_up(
+        &env.program_id,
+        &player.pubkey(),
+        ROOM,
+        STAKE,
+    )];
+    env.process(&ixs, &[&player]).await.unwrap();
+
+    // Nowa sesja zaczyna limit od stawki dołączenia
+    env.cash_out(0).await.unwrap();
+    env.join(0, STAKE).await.unwrap();
+    let state = env.player_state(&player.pubkey()).await.unwrap();
+    assert_eq!(state.session_stake, STAKE);
+    assert_eq!(state.stake_amount, max_stake + STAKE);
+    env.process(&ixs, &[&player]).await.unwrap();
+    assert_eq!(
+        env.player_state(&player.pubkey())
+            .await
+            .unwrap()
+            .session_stake,
+        2 * STAKE
+    );
+}
+
+#[tokio::test]
+async fn top_up_rejections() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+    let admin = env.admin.insecure_clone();
+    let ixs = [instruction::top_up(
+        &env.program_id,
+        &player.pubkey(),
+        ROOM,
+        STAKE,
+    )];
+    let zero = [instruction::top_up(
+        &env.program_id,
+        &player.pubkey(),
+        ROOM,
+        0,
+    )];
+
+    // Gracz bez aktywnej sesji dołącza przez JoinGame
+    env.join(0, STAKE).await.unwrap();
+    env.cash_out(0).await.unwrap();
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::PlayerNotActive);
+
+    env.join(0, STAKE).await.unwrap();
+    let result = env.process(&zero, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidTopUpAmount);
+
+    // Wstrzymane dołączenia wstrzymują też dopłaty
+    let pause = [instruction::set_paused(
+        &env.program_id,
+        &admin.pubkey(),
+        true,
+        false,
+        false,
+    )];
+    env.process(&pause, &[&admin]).await.unwrap();
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::ProgramPaused);
+}
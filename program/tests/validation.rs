@@ -0,0 +1,146 @@
+// program/tests/validation.rs
+mod common;
+
+use borsh::BorshSerialize;
+use common::*;
+use solana_io::{error::SolanaIoError, instruction};
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Signer,
+};
+
+const STAKE: u64 = LAMPORTS_PER_SOL / 10;
+
+#[tokio::test]
+async fn game_account_owned_by_another_program_is_rejected() {
+    let mut env = start_initialized(1).await;
+    let game_address = env.game_address();
+    let mut game_account = env.account(&game_address).await;
+    game_account.owner = Pubkey::new_unique();
+    env.set_account(&game_address, game_account);
+
+    let result = env.join(0, STAKE).await;
+    assert_custom_error(result, SolanaIoError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn forged_game_account_is_rejected() {
+    let mut env = start_initialized(1).await;
+
+    // Kopia prawdziwej gry pod adresem, który nie jest PDA
+    let game_address = env.game_address();
+    let forged_address = Pubkey::new_unique();
+    let forged_account = env.account(&game_address).await;
+    env.set_account(&forged_address, forged_account);
+
+    let mut ix = instruction::join_game(&env.program_id, &env.player(0), ROOM, STAKE);
+    ix.accounts[2].pubkey = forged_address;
+    let player = env.players[0].insecure_clone();
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidGameAccount);
+}
+
+#[tokio::test]
+async fn forged_player_state_is_rejected() {
+    let mut env = start_initialized(1).await;
+    env.join(0, STAKE).await.unwrap();
+    let player = env.players[0].insecure_clone();
+
+    // Stan gracza z napompowaną wartością pod adresem, który nie jest PDA
+    let state_address = env.player_state_address(&player.pubkey());
+    let mut forged_account = env.account(&state_address).await;
+    let mut forged_state = env.player_state(&player.pubkey()).await.unwrap();
+    forged_state.current_value = 100 * LAMPORTS_PER_SOL;
+    forged_account.data = forged_state.try_to_vec().unwrap();
+    let forged_address = Pubkey::new_unique();
+    env.set_account(&forged_address, forged_account.clone());
+
+    let mut ixs = env.cash_out_instructions(0).await;
+    ixs[1].accounts[1].pubkey = forged_address;
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+
+    // Ten sam stan pod prawdziwym PDA, ale należący do innego programu
+    forged_account.owner = Pubkey::new_unique();
+    env.set_account(&state_address, forged_account);
+    let result = env.cash_out(0).await;
+    assert_custom_error(result, SolanaIoError::InvalidAccountOwner);
+}
+
+#[tokio::test]
+async fn player_state_of_another_player_is_rejected() {
+    let mut env = start_initialized(2).await;
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, 2 * STAKE).await.unwrap();
+
+    // Gracz 0 próbuje wypłacić stan gracza 1
+    let mut ixs = env.cash_out_instructions(0).await;
+    ixs[1].accounts[1].pubkey = env.player_state_address(&env.player(1));
+    let player = env.players[0].insecure_clone();
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidPlayerStateAccount);
+    assert!(env.player_state(&env.player(1)).await.unwrap().is_active);
+}
+
+#[tokio::test]
+async fn duplicate_accounts_are_rejected() {
+    let mut env = start_initialized(2).await;
+    env.join(0, STAKE).await.unwrap();
+    env.join(1, STAKE).await.unwrap();
+    let server = env.server.insecure_clone();
+
+    let mut ix = instruction::update_player_value(
+        &env.program_id,
+        &server.pubkey(),
+        ROOM,
+        &env.player(0),
+        &env.player(1),
+        STAKE,
+    );
+    ix.accounts[2].pubkey = ix.accounts[1].pubkey;
+    let result = env.process(&[ix], &[&server]).await;
+    assert_custom_error(result, SolanaIoError::DuplicateAccount);
+
+    // Pokój podmieniony na konto gry
+    let mut ixs = env.cash_out_instructions(0).await;
+    ixs[1].accounts[3].pubkey = env.game_address();
+    let player = env.players[0].insecure_clone();
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::DuplicateAccount);
+}
+
+#[tokio::test]
+async fn readonly_accounts_are_rejected() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+
+    let mut ix = instruction::join_game(&env.program_id, &player.pubkey(), ROOM, STAKE);
+    ix.accounts[3].is_writable = false;
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::AccountNotWritable);
+
+    env.join(0, STAKE).await.unwrap();
+    let mut ixs = env.cash_out_instructions(0).await;
+    ixs[1].accounts[3].is_writable = false;
+    let result = env.process(&ixs, &[&player]).await;
+    assert_custom_error(result, SolanaIoError::AccountNotWritable);
+}
+
+#[tokio::test]
+async fn wrong_program_and_sysvar_accounts_are_rejected() {
+    let mut env = start_initialized(1).await;
+    let player = env.players[0].insecure_clone();
+
+    let mut ix = instruction::join_game(&env.program_id, &player.pubkey(), ROOM, STAKE);
+    ix.accounts[4].pubkey = Pubkey::new_unique();
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidSystemProgram);
+
+    let mut ix = instruction::join_game(&env.program_id, &player.pubkey(), ROOM, STAKE);
+    ix.accounts[5].pubkey = solana_sdk::sysvar::clock::id();
+    let result = env.process(&[ix], &[&player]).await;
+    assert_custom_error(result, SolanaIoError::InvalidSysvar);
+
+    assert!(env.player_state(&player.pubkey()).await.is_none());
+}
@@ -93,7 +93,8 @@ class RoomManager {
     return bestRoom;
   }
   
-  assignPlayerToRoom(playerAddress, nickname, initialStake) {
+  // roomId - pokój z PlayerState on-chain; bez niego wybieramy najlepszy wolny
+  assignPlayerToRoom(playerAddress, nickname, initialStake, roomId = null) {
     // Sprawdź czy gracz już jest w jakimś pokoju
     const existingRoomId = this.playerRoomMap.get(playerAddress);
     if (existingRoomId) {
@@ -104,9 +105,9 @@ class RoomManager {
       }
     }
     
-    const room = this.findBestRoom();
-    if (!room) {
-      console.error('No available rooms!');
+    const room = roomId ? this.rooms.get(roomId) : this.findBestRoom();
+    if (!room || room.players.size >= this.maxPlayersPerRoom) {
+      console.error(roomId ? `Room ${roomId} is not available!` : 'No available rooms!');
       return null;
     }
     
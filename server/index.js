@@ -13,6 +13,7 @@ const {
   TransactionInstruction,
   sendAndConfirmTransaction
 } = require('@solana/web3.js');
+const crypto = require('crypto');
 const fs = require('fs');
 const bs58 = require('bs58');
 const RoomManager = require('./game/RoomManager');
@@ -89,6 +90,72 @@ try {
   console.error('Error loading server wallet:', error);
 }
 
+// Bilet wypłaty ważny przez minutę - klient musi w tym czasie wysłać CashOut
+const CASH_OUT_TICKET_TTL_SECONDS = 60;
+
+// Wartości graczy, którzy rozpoczęli cash out: playerAddress -> lamporty z silnika gry
+const pendingCashOuts = new Map();
+
+// Odczytaj PlayerState z blockchainu (układ jak w programie, borsh)
+async function fetchPlayerState(playerPubkey) {
+  const [playerStatePDA] = await PublicKey.findProgramAddress(
+    [Buffer.from('player_state'), playerPubkey.toBuffer()],
+    PROGRAM_ID
+  );
+  const accountInfo = await connection.getAccountInfo(playerStatePDA);
+  if (!accountInfo) return null;
+  
+  const data = accountInfo.data;
+  return {
+    currentValue: data.readBigUInt64LE(40),
+    isActive: data[48] === 1,
+    cashoutNonce: data.readBigUInt64LE(74),
+    roomId: data.readUInt32LE(82)
+  };
+}
+
+// PDA pokoju on-chain - seed to id pokoju (u32 LE)
+async function findRoomPDA(roomId) {
+  const roomIdBuffer = Buffer.alloc(4);
+  roomIdBuffer.writeUInt32LE(roomId, 0);
+  return await PublicKey.findProgramAddress(
+    [Buffer.from('room'), roomIdBuffer],
+    PROGRAM_ID
+  );
+}
+
+// Bilety: [domena, program id, rodzaj biletu, treść] - jak w program/src/ticket.rs
+const TICKET_DOMAIN = Buffer.from('solana-io:ticket:v1');
+const TICKET_KIND_CASH_OUT = 0;
+
+function serializeTicketMessage(kind, body) {
+  return Buffer.concat([TICKET_DOMAIN, PROGRAM_ID.toBuffer(), Buffer.from([kind]), body]);
+}
+
+// CashOutTicket { player, amount, nonce, expiry } - treść biletu podpisywanego przez serwer
+function serializeCashOutTicket(ticket) {
+  const buffer = Buffer.alloc(32 + 8 + 8 + 8);
+  ticket.player.toBuffer().copy(buffer, 0);
+  buffer.writeBigUInt64LE(ticket.amount, 32);
+  buffer.writeBigUInt64LE(ticket.nonce, 40);
+  buffer.writeBigInt64LE(ticket.expiry, 48);
+  return buffer;
+}
+
+// Podpis ed25519 kluczem serwera - program sprawdza go przez precompile ed25519
+function signWithServerWallet(message) {
+  const privateKey = crypto.createPrivateKey({
+    key: {
+      kty: 'OKP',
+      crv: 'Ed25519',
+      d: Buffer.from(serverWallet.secretKey.slice(0, 32)).toString('base64url'),
+      x: serverWallet.publicKey.toBuffer().toString('base64url')
+    },
+    format: 'jwk'
+  });
+  return crypto.sign(null, message, privateKey);
+}
+
 // Funkcja do aktualizacji wartości gracza na blockchain
 async function updatePlayerValueOnChain(eaterAddress, eatenAddress, eatenValue) {
   if (!serverWallet) {
@@ -122,6 +189,14 @@ async function updatePlayerValueOnChain(eaterAddress, eatenAddress, eatenValue)
       PROGRAM_ID
     );
     
+    // Pokój on-chain zjadającego - zjedzony musi grać w tym samym
+    const eaterState = await fetchPlayerState(eaterPubkey);
+    if (!eaterState) {
+      console.error('Eater has no player state on chain:', eaterAddress);
+      return null;
+    }
+    const [roomPDA] = await findRoomPDA(eaterState.roomId);
+    
     // Serializuj dane instrukcji
     const instructionData = Buffer.alloc(1 + 32 + 32 + 8);
     instructionData.writeUInt8(2, 0); // UpdatePlayerValue instruction
@@ -135,7 +210,8 @@ async function updatePlayerValueOnChain(eaterAddress, eatenAddress, eatenValue)
         { pubkey: serverWallet.publicKey, isSigner: true, isWritable: true },
         { pubkey: eaterStatePDA, isSigner: false, isWritable: true },
         { pubkey: eatenStatePDA, isSigner: false, isWritable: true },
-        { pubkey: gamePDA, isSigner: false, isWritable: true },
+        { pubkey: gamePDA, isSigner: false, isWritable: false },
+        { pubkey: roomPDA, isSigner: false, isWritable: true },
       ],
       programId: PROGRAM_ID,
       data: instructionData
@@ -183,8 +259,12 @@ async function forceCleanupPlayer(playerAddress) {
       PROGRAM_ID
     );
     
-    // Platform fee wallet
-    const PLATFORM_FEE_WALLET = new PublicKey('FEEfBE29dqRgC8qMv6f9YXTSNbX7LMN3Reo3UsYdoUd8');
+    const playerState = await fetchPlayerState(playerPubkey);
+    if (!playerState) {
+      console.log('Player has no state on chain, nothing to cleanup');
+      return 'already_inactive';
+    }
+    const [roomPDA] = await findRoomPDA(playerState.roomId);
     
     // Serializuj dane instrukcji - ForceCleanup
     const instructionData = Buffer.alloc(1 + 32);
@@ -196,8 +276,8 @@ async function forceCleanupPlayer(playerAddress) {
       keys: [
         { pubkey: serverWallet.publicKey, isSigner: true, isWritable: true }, // Server authority
         { pubkey: playerStatePDA, isSigner: false, isWritable: true },
-        { pubkey: gamePDA, isSigner: false, isWritable: true },
-        { pubkey: PLATFORM_FEE_WALLET, isSigner: false, isWritable: false }, // Nie zapisujemy do fee wallet
+        { pubkey: gamePDA, isSigner: false, isWritable: false },
+        { pubkey: roomPDA, isSigner: false, isWritable: true },
         { pubkey: playerPubkey, isSigner: false, isWritable: false }, // Gracz nie otrzymuje środków
       ],
       programId: PROGRAM_ID,
@@ -230,6 +310,51 @@ async function forceCleanupPlayer(playerAddress) {
   }
 }
 
+// Heartbeat - dowód życia serwera dla programu
+// Bez niego po heartbeat_timeout (domyślnie 300s) gracze mogą użyć EmergencyWithdraw
+const HEARTBEAT_INTERVAL_MS = parseInt(process.env.HEARTBEAT_INTERVAL_MS || '60000', 10);
+
+async function sendHeartbeat() {
+  if (!serverWallet) {
+    return null;
+  }
+  
+  try {
+    const [gamePDA] = await PublicKey.findProgramAddress(
+      [Buffer.from('global_game')],
+      PROGRAM_ID
+    );
+    
+    // Serializuj dane instrukcji - Heartbeat
+    const instructionData = Buffer.alloc(1);
+    instructionData.writeUInt8(13, 0); // Heartbeat instruction (index 13)
+    
+    const instruction = new TransactionInstruction({
+      keys: [
+        { pubkey: serverWallet.publicKey, isSigner: true, isWritable: false }, // Server authority
+        { pubkey: gamePDA, isSigner: false, isWritable: true },
+      ],
+      programId: PROGRAM_ID,
+      data: instructionData
+    });
+    
+    const transaction = new Transaction().add(instruction);
+    
+    const signature = await sendAndConfirmTransaction(
+      connection,
+      transaction,
+      [serverWallet],
+      { commitment: 'confirmed' }
+    );
+    
+    return signature;
+    
+  } catch (error) {
+    console.error('Error sending heartbeat:', error);
+    return null;
+  }
+}
+
 // Inicjalizuj Room Manager
 const roomManager = new RoomManager();
 
@@ -294,6 +419,15 @@ app.get('/api/game/room/:roomId/status', (req, res) => {
   });
 });
 
+// Pokój dla nowego gracza - klient dołącza do niego on-chain (JoinGame.room_id)
+app.get('/api/game/assign-room', (req, res) => {
+  const room = roomManager.findBestRoom();
+  if (!room) {
+    return res.status(503).json({ error: 'All game rooms are full. Please try again later.' });
+  }
+  res.json({ roomId: room.id });
+});
+
 // Dołączanie do gry
 app.post('/api/game/join', async (req, res) => {
   try {
@@ -325,6 +459,9 @@ app.post('/api/game/cashout', async (req, res) => {
     
     console.log('Player cashing out:', playerAddress);
     
+    // Wypłata potwierdzona - bilet nie będzie już potrzebny
+    pendingCashOuts.delete(playerAddress);
+    
     // Znajdź pokój gracza
     const room = roomManager.getPlayerRoom(playerAddress);
     if (!room) {
@@ -349,6 +486,58 @@ app.post('/api/game/cashout', async (req, res) => {
   }
 });
 
+// Bilet wypłaty podpisany przez serwer - tylko na wartość, z którą gracz rozpoczął cash out
+app.post('/api/game/cashout-ticket', async (req, res) => {
+  try {
+    const { playerAddress } = req.body;
+    
+    if (!serverWallet) {
+      return res.status(503).json({ error: 'Server wallet not configured' });
+    }
+    
+    const amount = pendingCashOuts.get(playerAddress);
+    if (amount === undefined) {
+      return res.status(404).json({ error: 'No pending cash out for player' });
+    }
+    
+    const playerPubkey = new PublicKey(playerAddress);
+    const playerState = await fetchPlayerState(playerPubkey);
+    if (!playerState || !playerState.isActive) {
+      return res.status(404).json({ error: 'Player is not active on chain' });
+    }
+    
+    // Program przyjmie bilet tylko na wartość z blockchainu - zjedzenie może być jeszcze w drodze
+    if (playerState.currentValue !== BigInt(amount)) {
+      console.log(`Cash out ticket refused for ${playerAddress}: game value ${amount}, on-chain ${playerState.currentValue}`);
+      return res.status(409).json({ error: 'Player value is not yet confirmed on chain, try again shortly' });
+    }
+    
+    const ticket = {
+      player: playerPubkey,
+      amount: playerState.currentValue,
+      nonce: playerState.cashoutNonce,
+      expiry: BigInt(Math.floor(Date.now() / 1000) + CASH_OUT_TICKET_TTL_SECONDS)
+    };
+    const signature = signWithServerWallet(
+      serializeTicketMessage(TICKET_KIND_CASH_OUT, serializeCashOutTicket(ticket))
+    );
+    
+    res.json({
+      ticket: {
+        player: playerAddress,
+        amount: ticket.amount.toString(),
+        nonce: ticket.nonce.toString(),
+        expiry: ticket.expiry.toString()
+      },
+      signature: signature.toString('base64'),
+      serverAuthority: serverWallet.publicKey.toString()
+    });
+  } catch (error) {
+    console.error('Error issuing cash out ticket:', error);
+    res.status(500).json({ error: 'Failed to issue cash out ticket', details: error.message });
+  }
+});
+
 // NOWY ENDPOINT: Force cleanup gracza
 app.post('/api/game/force-cleanup', async (req, res) => {
   try {
@@ -473,7 +662,7 @@ io.on('connection', (socket) => {
   });
   
   // Game handlers
-  socket.on('join_game', ({ playerAddress, nickname, initialStake }) => {
+  socket.on('join_game', async ({ playerAddress, nickname, initialStake }) => {
     console.log('Join game request:', { playerAddress, nickname, initialStake });
     
     // Sprawdź czy gracz już jest w jakimś pokoju
@@ -518,8 +707,19 @@ io.on('connection', (socket) => {
       }
     }
     
+    // Stawka leży w pokoju on-chain z PlayerState - silnik gry musi użyć tego samego
+    let chainRoomId = null;
+    try {
+      const playerState = await fetchPlayerState(new PublicKey(playerAddress));
+      if (playerState && playerState.isActive) {
+        chainRoomId = playerState.roomId;
+      }
+    } catch (error) {
+      console.error('Error reading on-chain room for player:', error);
+    }
+    
     // Przydziel gracza do pokoju
-    const room = roomManager.assignPlayerToRoom(playerAddress, nickname, initialStake);
+    const room = roomManager.assignPlayerToRoom(playerAddress, nickname, initialStake, chainRoomId);
     
     if (!room) {
       socket.emit('error', {
@@ -589,8 +789,9 @@ io.on('connection', (socket) => {
     console.log(`- Players eaten: ${player.playersEaten}`);
     console.log(`- Total earned: ${player.totalSolEarned} lamports`);
     
-    // Usuń gracza z gry
+    // Usuń gracza z gry - wartość zostaje do wystawienia biletu wypłaty
     roomManager.removePlayerFromRoom(playerAddress, true);
+    pendingCashOuts.set(playerAddress, cashOutAmount);
     
     // Usuń mapowania socketów
     playerSockets.delete(playerAddress);
@@ -738,6 +939,10 @@ setInterval(() => {
   console.log('=======================');
 }, 60000);
 
+// Heartbeat - interwał dużo krótszy niż heartbeat_timeout, żeby pojedyncza
+// nieudana transakcja nie otwierała EmergencyWithdraw
+setInterval(sendHeartbeat, HEARTBEAT_INTERVAL_MS);
+
 // Start server
 const PORT = process.env.PORT || 3001;
 server.listen(PORT, () => {
@@ -748,6 +953,9 @@ server.listen(PORT, () => {
   console.log(`Blockchain updates: ${serverWallet ? 'ENABLED' : 'DISABLED'}`);
   console.log(`Total capacity: ${roomManager.maxRooms} rooms x ${roomManager.maxPlayersPerRoom} players = ${roomManager.maxRooms * roomManager.maxPlayersPerRoom} players`);
   console.log('Multi-room system is active!');
+  
+  // Pierwszy heartbeat od razu - po restarcie nie czekamy na interwał
+  sendHeartbeat();
 });
 
 // Graceful shutdown
@@ -1,6 +1,6 @@
 // src/components/CashOutScreen.js
 import React, { useState, useEffect } from 'react';
-import { cashOut } from '../utils/SolanaTransactions';
+import { cashOut, isProgramPausedError } from '../utils/SolanaTransactions';
 import './CashOutScreen.css';
 
 export default function CashOutScreen({ pendingCashOut, wallet, onComplete }) {
@@ -65,7 +65,11 @@ export default function CashOutScreen({ pendingCashOut, wallet, onComplete }) {
       
     } catch (error) {
       console.error('Cash out error:', error);
-      setError(error.message || 'Failed to process cash out');
+      if (isProgramPausedError(error)) {
+        setError('Cash outs are temporarily paused for maintenance. Your balance is safe - please try again later.');
+      } else {
+        setError(error.message || 'Failed to process cash out');
+      }
       setIsProcessing(false);
     }
   };
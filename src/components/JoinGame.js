@@ -2,7 +2,7 @@
 import React, { useState, useEffect } from 'react';
 import { useWallet } from '@solana/wallet-adapter-react';
 import { PublicKey } from '@solana/web3.js';
-import { joinGlobalGame, initializeGlobalGame, checkGlobalGameState } from '../utils/SolanaTransactions';
+import { joinGlobalGame, requestRoomAssignment, initializeGlobalGame, checkGlobalGameState, isProgramPausedError } from '../utils/SolanaTransactions';
 import './JoinGame.css';
 
 export default function JoinGame({ onJoinGame, socket }) {
@@ -190,14 +190,21 @@ export default function JoinGame({ onJoinGame, socket }) {
       setIsJoining(true);
       setError('');
       
+      // Serwer wybiera pokój, stawka trafia do jego puli on-chain
+      const roomId = await requestRoomAssignment();
+      
       // Execute blockchain transaction
-      const result = await joinGlobalGame(stakeAmount, wallet);
+      const result = await joinGlobalGame(stakeAmount, wallet, roomId);
       
       // If successful, proceed to game
       onJoinGame(result.stakeInLamports, nickname.trim());
     } catch (error) {
       console.error('Error joining game:', error);
-      setError(`Failed to join: ${error.message}`);
+      if (isProgramPausedError(error)) {
+        setError('The game is under maintenance. Please try again later.');
+      } else {
+        setError(`Failed to join: ${error.message}`);
+      }
     } finally {
       setIsJoining(false);
     }
@@ -6,7 +6,9 @@ import {
   PublicKey, 
   LAMPORTS_PER_SOL,
   clusterApiUrl,
-  TransactionInstruction
+  TransactionInstruction,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY
 } from '@solana/web3.js';
 import { Buffer } from 'buffer';
 
@@ -20,7 +22,6 @@ console.log('Using game server URL:', GAME_SERVER_URL);
 
 // Program ID - ZAKTUALIZUJ PO DEPLOYU!
 const PROGRAM_ID = new PublicKey('J4CuZ3NrqppFQ8gjrBgxMheNPui4RxF3S1CoeEeKWWqv');
-const PLATFORM_FEE_WALLET = new PublicKey('FEEfBE29dqRgC8qMv6f9YXTSNbX7LMN3Reo3UsYdoUd8');
 
 console.log('Solana configuration loaded:', {
   NETWORK,
@@ -30,18 +31,22 @@ console.log('Solana configuration loaded:', {
 
 // ========== SERIALIZACJA DANYCH ==========
 
-function serializeInitializeGameData(serverAuthority) {
-  const buffer = Buffer.alloc(1 + 32);
+function serializeInitializeGameData(serverAuthority, adminAuthority, platformFeeWallet) {
+  const buffer = Buffer.alloc(1 + 32 + 32 + 32);
   buffer.writeUInt8(0, 0); // InitializeGame instruction
   serverAuthority.toBuffer().copy(buffer, 1);
+  adminAuthority.toBuffer().copy(buffer, 33);
+  platformFeeWallet.toBuffer().copy(buffer, 65);
   return buffer;
 }
 
-function serializeJoinGameData(stakeAmount) {
-  const buffer = Buffer.alloc(1 + 8);
+function serializeJoinGameData(stakeAmount, roomId) {
+  const buffer = Buffer.alloc(1 + 4 + 8);
   buffer.writeUInt8(1, 0); // JoinGame instruction
+  // Kolejność pól jak w JoinGame { room_id, stake_amount }
+  buffer.writeUInt32LE(roomId, 1);
   const lamportsAmount = Math.floor(stakeAmount * LAMPORTS_PER_SOL);
-  buffer.writeBigUInt64LE(BigInt(lamportsAmount), 1);
+  buffer.writeBigUInt64LE(BigInt(lamportsAmount), 5);
   return buffer;
 }
 
@@ -54,9 +59,30 @@ function serializeUpdatePlayerValueData(player, eatenPlayer, eatenValue) {
   return buffer;
 }
 
-function serializeCashOutData() {
-  const buffer = Buffer.alloc(1);
+// Bilety: [domena, program id, rodzaj biletu, treść] - jak w program/src/ticket.rs
+const TICKET_DOMAIN = Buffer.from('solana-io:ticket:v1');
+const TICKET_KIND_CASH_OUT = 0;
+
+function serializeTicketMessage(kind, body) {
+  return Buffer.concat([TICKET_DOMAIN, PROGRAM_ID.toBuffer(), Buffer.from([kind]), body]);
+}
+
+// CashOutTicket { player, amount, nonce, expiry } - treść biletu podpisanego przez serwer
+function serializeCashOutTicket(ticket) {
+  const buffer = Buffer.alloc(32 + 8 + 8 + 8);
+  new PublicKey(ticket.player).toBuffer().copy(buffer, 0);
+  buffer.writeBigUInt64LE(BigInt(ticket.amount), 32);
+  buffer.writeBigUInt64LE(BigInt(ticket.nonce), 40);
+  buffer.writeBigInt64LE(BigInt(ticket.expiry), 48);
+  return buffer;
+}
+
+function serializeCashOutData(ticket, close = false) {
+  const ticketData = serializeCashOutTicket(ticket);
+  const buffer = Buffer.alloc(1 + ticketData.length + 1);
   buffer.writeUInt8(3, 0); // CashOut instruction
+  ticketData.copy(buffer, 1);
+  buffer.writeUInt8(close ? 1 : 0, 1 + ticketData.length); // close: zamknij stan gracza
   return buffer;
 }
 
@@ -69,6 +95,23 @@ async function findGlobalGamePDA() {
   );
 }
 
+async function findFeeVaultPDA() {
+  return await PublicKey.findProgramAddress(
+    [Buffer.from('fee_vault')],
+    PROGRAM_ID
+  );
+}
+
+// PDA pokoju on-chain - seed to id pokoju (u32 LE)
+async function findRoomPDA(roomId) {
+  const roomIdBuffer = Buffer.alloc(4);
+  roomIdBuffer.writeUInt32LE(roomId, 0);
+  return await PublicKey.findProgramAddress(
+    [Buffer.from('room'), roomIdBuffer],
+    PROGRAM_ID
+  );
+}
+
 async function findPlayerStatePDA(playerPubkey) {
   return await PublicKey.findProgramAddress(
     [Buffer.from('player_state'), playerPubkey.toBuffer()],
@@ -115,10 +158,14 @@ export async function checkPlayerState(wallet) {
     // joined_at: 8 bytes (49-56)
     // last_cashout: 8 bytes (57-64)
     // total_earned: 8 bytes (65-72)
+    // bump: 1 byte (73)
+    // cashout_nonce: 8 bytes (74-81)
+    // room_id: 4 bytes (82-85)
     
     const isActive = accountInfo.data[48] === 1;
     const currentValue = accountInfo.data.readBigUInt64LE(40);
     const stakeAmount = accountInfo.data.readBigUInt64LE(32);
+    const roomId = accountInfo.data.readUInt32LE(82);
     
     console.log('Player state found:', {
       isActive,
@@ -131,7 +178,8 @@ export async function checkPlayerState(wallet) {
       isActive,
       currentValue: Number(currentValue) / LAMPORTS_PER_SOL,
       stakeAmount: Number(stakeAmount) / LAMPORTS_PER_SOL,
-      currentValueLamports: Number(currentValue)
+      currentValueLamports: Number(currentValue),
+      roomId
     };
   } catch (error) {
     console.error('Error checking player state:', error);
@@ -175,7 +223,9 @@ export async function checkGlobalGameState() {
 }
 
 // Inicjalizacja globalnej gry (tylko raz, przez admina)
-export async function initializeGlobalGame(wallet, serverAuthority = null) {
+// adminAuthority - klucz do parametrów i prowizji, domyślnie portfel inicjalizujący
+// platformFeeWallet - portfel na prowizje, domyślnie portfel inicjalizujący
+export async function initializeGlobalGame(wallet, serverAuthority = null, adminAuthority = null, platformFeeWallet = null) {
   const { publicKey, signTransaction } = wallet;
   
   if (!publicKey) throw new Error('Wallet not connected');
@@ -189,6 +239,7 @@ export async function initializeGlobalGame(wallet, serverAuthority = null) {
   }
   
   const [gamePDA] = await findGlobalGamePDA();
+  const [feeVaultPDA] = await findFeeVaultPDA();
   
   // Sprawdź czy gra już istnieje
   const accountInfo = await connection.getAccountInfo(gamePDA);
@@ -197,12 +248,14 @@ export async function initializeGlobalGame(wallet, serverAuthority = null) {
     return { alreadyInitialized: true };
   }
   
-  const data = serializeInitializeGameData(serverAuthority);
+  const admin = adminAuthority || publicKey;
+  const data = serializeInitializeGameData(serverAuthority, admin, platformFeeWallet || publicKey);
   
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: publicKey, isSigner: true, isWritable: true },
       { pubkey: gamePDA, isSigner: false, isWritable: true },
+      { pubkey: feeVaultPDA, isSigner: false, isWritable: true }, // Skarbiec prowizji tworzony razem z grą
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: new PublicKey('SysvarRent111111111111111111111111111111111'), isSigner: false, isWritable: false },
     ],
@@ -224,7 +277,8 @@ export async function initializeGlobalGame(wallet, serverAuthority = null) {
     signature
   }, 'confirmed');
   
-  console.log('Global game initialized with server authority:', serverAuthority.toString());
+  console.log('Global game initialized with server authority:', serverAuthority.toString(),
+              'admin authority:', admin.toString());
   
   return { success: true, signature };
 }
@@ -257,8 +311,21 @@ export async function requestForceCleanup(playerAddress) {
   }
 }
 
-// Dołączanie do globalnej gry - ZAKTUALIZOWANE
-export async function joinGlobalGame(stakeAmount, wallet) {
+// Pokój przydzielony przez serwer gry - do niego trafia stawka on-chain
+export async function requestRoomAssignment() {
+  const response = await fetch(`${GAME_SERVER_URL}/api/game/assign-room`);
+  
+  if (!response.ok) {
+    const errorData = await response.json();
+    throw new Error(errorData.error || 'Failed to get a game room');
+  }
+  
+  const { roomId } = await response.json();
+  return roomId;
+}
+
+// Dołączanie do gry - roomId z requestRoomAssignment
+export async function joinGlobalGame(stakeAmount, wallet, roomId) {
   const { publicKey, signTransaction } = wallet;
   
   if (!publicKey) throw new Error('Wallet not connected');
@@ -302,9 +369,11 @@ export async function joinGlobalGame(stakeAmount, wallet) {
   
   const [gamePDA] = await findGlobalGamePDA();
   const [playerStatePDA] = await findPlayerStatePDA(publicKey);
+  const [roomPDA] = await findRoomPDA(roomId);
   
   console.log('Joining global game:', {
     stakeAmount,
+    roomId,
     gamePDA: gamePDA.toString(),
     playerStatePDA: playerStatePDA.toString(),
     existingPlayer: playerState?.exists,
@@ -312,13 +381,14 @@ export async function joinGlobalGame(stakeAmount, wallet) {
     currentValue: playerState?.currentValueLamports
   });
   
-  const data = serializeJoinGameData(stakeAmount);
+  const data = serializeJoinGameData(stakeAmount, roomId);
   
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: publicKey, isSigner: true, isWritable: true },
       { pubkey: playerStatePDA, isSigner: false, isWritable: true },
-      { pubkey: gamePDA, isSigner: false, isWritable: true },
+      { pubkey: gamePDA, isSigner: false, isWritable: false },
+      { pubkey: roomPDA, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: new PublicKey('SysvarRent111111111111111111111111111111111'), isSigner: false, isWritable: false },
     ],
@@ -379,6 +449,11 @@ export async function updatePlayerValue(eaterAddress, eatenAddress, eatenValue,
   const [eaterStatePDA] = await findPlayerStatePDA(eaterPubkey);
   const [eatenStatePDA] = await findPlayerStatePDA(eatenPubkey);
   
+  // Obaj gracze grają w pokoju zjadającego - jego pula się zmienia
+  const eaterState = await checkPlayerState({ publicKey: eaterPubkey });
+  if (!eaterState) throw new Error('Eater has no player state');
+  const [roomPDA] = await findRoomPDA(eaterState.roomId);
+  
   const data = serializeUpdatePlayerValueData(eaterPubkey, eatenPubkey, eatenValue);
   
   const instruction = new TransactionInstruction({
@@ -386,7 +461,8 @@ export async function updatePlayerValue(eaterAddress, eatenAddress, eatenValue,
       { pubkey: publicKey, isSigner: true, isWritable: true }, // Authority (server)
       { pubkey: eaterStatePDA, isSigner: false, isWritable: true },
       { pubkey: eatenStatePDA, isSigner: false, isWritable: true },
-      { pubkey: gamePDA, isSigner: false, isWritable: true },
+      { pubkey: gamePDA, isSigner: false, isWritable: false },
+      { pubkey: roomPDA, isSigner: false, isWritable: true },
     ],
     programId: PROGRAM_ID,
     data: data
@@ -421,6 +497,22 @@ export async function updatePlayerValue(eaterAddress, eatenAddress, eatenValue,
   return { success: true, signature };
 }
 
+// Bilet wypłaty od serwera gry - podpis server authority sprawdzany przez program
+async function requestCashOutTicket(playerAddress) {
+  const response = await fetch(`${GAME_SERVER_URL}/api/game/cashout-ticket`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ playerAddress })
+  });
+  
+  if (!response.ok) {
+    const errorData = await response.json();
+    throw new Error(errorData.error || 'Failed to get cash out ticket');
+  }
+  
+  return await response.json();
+}
+
 // Cash out - wypłata i wyjście z gry
 export async function cashOut(wallet) {
   const { publicKey, signTransaction } = wallet;
@@ -437,33 +529,50 @@ export async function cashOut(wallet) {
   }
   
   const pendingCashOut = JSON.parse(pendingCashOutData);
-  const currentValueLamports = pendingCashOut.amount || 0;
-  const currentValueSol = currentValueLamports / 1000000000;
-  
-  if (currentValueLamports === 0) {
+  if (!pendingCashOut.amount) {
     throw new Error('You have no SOL to cash out');
   }
   
-  console.log('Cashing out with server value:', {
+  const playerState = await checkPlayerState(wallet);
+  if (!playerState?.isActive) {
+    throw new Error('Player is not active on chain');
+  }
+  const [roomPDA] = await findRoomPDA(playerState.roomId);
+  
+  // Serwer podpisuje bilet na wartość z blockchainu
+  const { ticket, signature: ticketSignature, serverAuthority } = await requestCashOutTicket(publicKey.toString());
+  const currentValueLamports = Number(ticket.amount);
+  const currentValueSol = currentValueLamports / LAMPORTS_PER_SOL;
+  
+  console.log('Cashing out with server ticket:', {
     currentValueLamports,
     currentValueSol,
-    pendingCashOut
+    nonce: ticket.nonce,
+    roomId: playerState.roomId
+  });
+  
+  // Instrukcja ed25519 musi stać bezpośrednio przed CashOut
+  const ticketInstruction = Ed25519Program.createInstructionWithPublicKey({
+    publicKey: new PublicKey(serverAuthority).toBytes(),
+    message: serializeTicketMessage(TICKET_KIND_CASH_OUT, serializeCashOutTicket(ticket)),
+    signature: Buffer.from(ticketSignature, 'base64')
   });
   
-  const data = serializeCashOutData();
+  const data = serializeCashOutData(ticket);
   
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: publicKey, isSigner: true, isWritable: true },
       { pubkey: playerStatePDA, isSigner: false, isWritable: true },
-      { pubkey: gamePDA, isSigner: false, isWritable: true },
-      { pubkey: PLATFORM_FEE_WALLET, isSigner: false, isWritable: true },
+      { pubkey: gamePDA, isSigner: false, isWritable: false },
+      { pubkey: roomPDA, isSigner: false, isWritable: true }, // Prowizja zostaje w pokoju
+      { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
     ],
     programId: PROGRAM_ID,
     data: data
   });
   
-  const transaction = new Transaction().add(instruction);
+  const transaction = new Transaction().add(ticketInstruction, instruction);
   const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash();
   transaction.recentBlockhash = blockhash;
   transaction.feePayer = publicKey;
@@ -507,5 +616,15 @@ export async function cashOut(wallet) {
   };
 }
 
+// Kod błędu SolanaIoError::ProgramPaused - admin wstrzymał program (SetPaused)
+export const PROGRAM_PAUSED_ERROR_CODE = 31;
+
+// Czy błąd transakcji to wstrzymanie programu (przerwa techniczna)
+export function isProgramPausedError(error) {
+  const hexCode = `custom program error: 0x${PROGRAM_PAUSED_ERROR_CODE.toString(16)}`;
+  const messages = [error?.message, ...(error?.logs || [])].filter(Boolean);
+  return messages.some((message) => message.includes(hexCode));
+}
+
 // Export connection dla innych komponentów jeśli potrzebują
 export { connection, PROGRAM_ID };
\ No newline at end of file